@@ -0,0 +1,30 @@
+//! Works out how wide the tags are, so that metadata types that need wider tags can be left out.
+//!
+//! `FAT_POINTER_HACK_POINTER_WIDTH` pretends the target's pointers are narrower than they are,
+//! which is how the UI tests check the diagnostics of a 16-bit target on any host.
+
+use std::env;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=FAT_POINTER_HACK_POINTER_WIDTH");
+    println!("cargo:rustc-check-cfg=cfg(tag_width_32, tag_width_64, trybuild)");
+
+    let target: u32 = env::var("CARGO_CFG_TARGET_POINTER_WIDTH")
+        .expect("cargo sets the target pointer width")
+        .parse()
+        .expect("the target pointer width is a number");
+    let width = match env::var("FAT_POINTER_HACK_POINTER_WIDTH") {
+        Ok(simulated) => simulated
+            .parse::<u32>()
+            .expect("FAT_POINTER_HACK_POINTER_WIDTH must be a number")
+            .min(target),
+        Err(_) => target,
+    };
+
+    if width >= 32 {
+        println!("cargo:rustc-cfg=tag_width_32");
+    }
+    if width >= 64 {
+        println!("cargo:rustc-cfg=tag_width_64");
+    }
+}
@@ -0,0 +1,85 @@
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use super::{BitEqFloat, SmallStr, Tag, TypeTag};
+#[cfg(tag_width_64)]
+use super::{CharPair, Generation, Pair, Quad};
+
+/// Any `usize` is a valid Tag, so this just picks a random one.
+///
+/// The primitive metadata types already implement `Arbitrary` themselves,
+/// and so do this crate's own metadata types with this feature.
+/// ```
+/// # extern crate arbitrary;
+/// # extern crate fat_pointer_hack;
+/// use arbitrary::{Arbitrary, Unstructured};
+/// use fat_pointer_hack::{Metadata, SmallStr, Tag, TypeTag};
+///
+/// # fn main() {
+/// let mut u = Unstructured::new(&[7, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 2, b'o', b'k']);
+/// let tag = Tag::arbitrary(&mut u).unwrap();
+/// let type_tag = TypeTag::arbitrary(&mut u).unwrap();
+/// assert_eq!(usize::unpack(tag), 7);
+/// assert_eq!(type_tag, TypeTag(3));
+///
+/// let mut u = Unstructured::new(&[0xFF; 64]);
+/// let small = SmallStr::arbitrary(&mut u).unwrap();
+/// assert!(small.as_str().len() <= std::mem::size_of::<usize>());
+/// # }
+/// ```
+impl<'a> Arbitrary<'a> for Tag {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Tag(usize::arbitrary(u)?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for TypeTag {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(TypeTag(u32::arbitrary(u)?))
+    }
+}
+
+/// Picks an arbitrary string and cuts it down until it fits.
+impl<'a> Arbitrary<'a> for SmallStr {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let s = <&str>::arbitrary(u)?;
+        let mut end = s.len().min(core::mem::size_of::<usize>());
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        Ok(SmallStr::new(s[..end].trim_end_matches('\0')).expect("the string was cut to fit"))
+    }
+}
+
+impl<'a, F: Arbitrary<'a>> Arbitrary<'a> for BitEqFloat<F> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(BitEqFloat(F::arbitrary(u)?))
+    }
+}
+
+#[cfg(tag_width_64)]
+impl<'a> Arbitrary<'a> for Generation {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Generation::new(u16::arbitrary(u)?, u32::arbitrary(u)?))
+    }
+}
+
+#[cfg(tag_width_64)]
+impl<'a, T: Arbitrary<'a>> Arbitrary<'a> for Pair<T> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Pair(T::arbitrary(u)?, T::arbitrary(u)?))
+    }
+}
+
+#[cfg(tag_width_64)]
+impl<'a, T: Arbitrary<'a>> Arbitrary<'a> for Quad<T> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Quad(<[T; 4]>::arbitrary(u)?))
+    }
+}
+
+#[cfg(tag_width_64)]
+impl<'a> Arbitrary<'a> for CharPair {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(CharPair(char::arbitrary(u)?, char::arbitrary(u)?))
+    }
+}
@@ -0,0 +1,51 @@
+use super::{FatRef, FatRefExt, FatRefMut, FatRefMutExt, Metadata};
+
+/// An extension trait for tagging all elements of an array at once.
+///
+/// It is implemented for `&[T; N]` and `&mut [T; N]`.
+/// The mutable version hands out one FatRefMut per element,
+/// so distinct elements can be mutated at the same time.
+///
+/// ```
+/// # use fat_pointer_hack::{ArrayRefExt, FatRefExt, FatRefMutExt};
+/// let values = [10, 20, 30];
+/// let tagged = (&values).tag_each(|i, _| i);
+/// for (i, fat) in tagged.iter().enumerate() {
+///     assert_eq!(fat.get_tag(), i);
+///     assert_eq!(fat.as_ptr(), &values[i] as *const i32);
+/// }
+///
+/// let mut values = [1, 2, 3];
+/// let [a, b, c] = (&mut values).tag_each(|_, v: &i32| *v as usize * 10);
+/// *a.as_mut() += c.get_tag() as i32;
+/// *c.as_mut() += b.get_tag() as i32;
+/// assert_eq!(values, [31, 2, 23]);
+/// ```
+pub trait ArrayRefExt<'a, T: 'a, const N: usize> {
+    type Output<M: 'a + Metadata>;
+    fn tag_each<M: 'a + Metadata>(self, f: impl FnMut(usize, &T) -> M) -> [Self::Output<M>; N];
+}
+
+impl<'a, T: 'a, const N: usize> ArrayRefExt<'a, T, N> for &'a [T; N] {
+    type Output<M: 'a + Metadata> = FatRef<'a, T, M>;
+    fn tag_each<M: 'a + Metadata>(self, mut f: impl FnMut(usize, &T) -> M) -> [FatRef<'a, T, M>; N] {
+        let mut index = 0;
+        self.each_ref().map(|value| {
+            let tag = f(index, value);
+            index += 1;
+            FatRef::from_ref(value, tag)
+        })
+    }
+}
+
+impl<'a, T: 'a, const N: usize> ArrayRefExt<'a, T, N> for &'a mut [T; N] {
+    type Output<M: 'a + Metadata> = FatRefMut<'a, T, M>;
+    fn tag_each<M: 'a + Metadata>(self, mut f: impl FnMut(usize, &T) -> M) -> [FatRefMut<'a, T, M>; N] {
+        let mut index = 0;
+        self.each_mut().map(|value| {
+            let tag = f(index, value);
+            index += 1;
+            FatRefMut::from_ref_mut(value, tag)
+        })
+    }
+}
@@ -0,0 +1,56 @@
+use super::{FatRef, FatRefMut, Metadata};
+
+/// A trait for everything that can be borrowed as a `FatRef<P, M>`.
+///
+/// This lets generic code accept shared and mutable fat references (or owning handles like
+/// `FatArc`) alike.
+///
+/// ```
+/// # use fat_pointer_hack::{AsFatRef, FatRefExt, RefExt};
+/// fn read_tag(fat: impl AsFatRef<i32, usize>) -> usize {
+///     fat.fat().get_tag()
+/// }
+///
+/// let mut x = 5;
+/// assert_eq!(read_tag((&x).tag(1)), 1);
+/// assert_eq!(read_tag((&mut x).tag(2)), 2);
+/// ```
+pub trait AsFatRef<P, M: Metadata> {
+    fn fat(&self) -> FatRef<'_, P, M>;
+}
+
+/// A trait for everything that can be borrowed as a `FatRefMut<P, M>`.
+///
+/// `fat_mut` hands out a new FatRefMut with a copy of the tag, so it gives mutable access
+/// to the pointee only. Changing the tag of that FatRefMut doesn't change the tag of the implementor.
+/// ```
+/// # use fat_pointer_hack::{AsFatRefMut, FatRefMutExt, RefExt};
+/// fn bump(mut fat: impl AsFatRefMut<i32, usize>) {
+///     *fat.fat_mut().to_plain_mut() += 1;
+/// }
+///
+/// let mut x = 5;
+/// bump((&mut x).tag(1));
+/// assert_eq!(x, 6);
+/// ```
+pub trait AsFatRefMut<P, M: Metadata>: AsFatRef<P, M> {
+    fn fat_mut(&mut self) -> FatRefMut<'_, P, M>;
+}
+
+impl<'a, P, M: Metadata> AsFatRef<P, M> for FatRef<'a, P, M> {
+    fn fat(&self) -> FatRef<'_, P, M> {
+        self
+    }
+}
+
+impl<'a, P, M: Metadata> AsFatRef<P, M> for FatRefMut<'a, P, M> {
+    fn fat(&self) -> FatRef<'_, P, M> {
+        self
+    }
+}
+
+impl<'a, P, M: Metadata> AsFatRefMut<P, M> for FatRefMut<'a, P, M> {
+    fn fat_mut(&mut self) -> FatRefMut<'_, P, M> {
+        self
+    }
+}
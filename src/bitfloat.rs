@@ -0,0 +1,87 @@
+use core::hash::{Hash, Hasher};
+
+#[cfg(tag_width_32)]
+use super::{Metadata, Tag};
+
+/// A float tag that compares and hashes by its bits, so it can be `Eq` and `Hash`.
+///
+/// Unlike for the float itself, NaN is equal to itself (given the same payload),
+/// and `0.0` and `-0.0` are different.
+/// ```
+/// # use fat_pointer_hack::{RefExt, FatRefExt, BitEqFloat};
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::collections::HashSet;
+/// use std::hash::{Hash, Hasher};
+///
+/// fn hash_of<T: Hash>(value: &T) -> u64 {
+///     let mut hasher = DefaultHasher::new();
+///     value.hash(&mut hasher);
+///     hasher.finish()
+/// }
+///
+/// let x = 5;
+/// let nan = (&x).tag(BitEqFloat(f64::NAN));
+/// assert_eq!(nan.get_tag(), BitEqFloat(f64::NAN));
+/// assert_eq!(hash_of(&nan.get_tag()), hash_of(&BitEqFloat(f64::NAN)));
+/// assert_ne!(BitEqFloat(0.0f64), BitEqFloat(-0.0));
+/// assert_eq!(BitEqFloat(-0.0f32), BitEqFloat(-0.0));
+///
+/// let tags: HashSet<_> = [f64::NAN, f64::NAN, 0.0, -0.0, 1.5]
+///     .iter()
+///     .map(|&f| (&x).tag(BitEqFloat(f)).get_tag())
+///     .collect();
+/// assert_eq!(tags.len(), 4);
+/// assert!(tags.contains(&BitEqFloat(f64::NAN)));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BitEqFloat<F>(pub F);
+
+impl PartialEq for BitEqFloat<f64> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+
+impl Eq for BitEqFloat<f64> {}
+
+impl Hash for BitEqFloat<f64> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+impl PartialEq for BitEqFloat<f32> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+
+impl Eq for BitEqFloat<f32> {}
+
+impl Hash for BitEqFloat<f32> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+#[cfg(tag_width_64)]
+#[cfg_attr(trybuild, diagnostic::do_not_recommend)]
+impl Metadata for BitEqFloat<f64> {
+    fn pack(self) -> Tag {
+        self.0.pack()
+    }
+    fn unpack(val: Tag) -> Self {
+        BitEqFloat(f64::unpack(val))
+    }
+}
+
+#[cfg(tag_width_32)]
+#[cfg_attr(trybuild, diagnostic::do_not_recommend)]
+impl Metadata for BitEqFloat<f32> {
+    fn pack(self) -> Tag {
+        self.0.pack()
+    }
+    fn unpack(val: Tag) -> Self {
+        BitEqFloat(f32::unpack(val))
+    }
+}
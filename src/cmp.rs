@@ -0,0 +1,43 @@
+use super::{FatPointee, Metadata, Tag};
+
+/// Anything that has a pointee and a tag to compare against, see `FatRefExt::eq_tagged`.
+///
+/// This is implemented for fat references (including references to them)
+/// and for `(&P, &M)` pairs, so FatRefs can be compared with plain values.
+pub trait TaggedCmp {
+    type Target: ?Sized;
+    type Meta;
+    fn tagged_parts(&self) -> (&Self::Target, Self::Meta);
+}
+
+impl<P, M: Metadata> TaggedCmp for FatPointee<P, M> {
+    type Target = P;
+    type Meta = M;
+    fn tagged_parts(&self) -> (&P, M) {
+        (&self.pointee, M::unpack(Tag(self.unsize.len())))
+    }
+}
+
+impl<T: TaggedCmp + ?Sized> TaggedCmp for &T {
+    type Target = T::Target;
+    type Meta = T::Meta;
+    fn tagged_parts(&self) -> (&T::Target, T::Meta) {
+        (**self).tagged_parts()
+    }
+}
+
+impl<T: TaggedCmp + ?Sized> TaggedCmp for &mut T {
+    type Target = T::Target;
+    type Meta = T::Meta;
+    fn tagged_parts(&self) -> (&T::Target, T::Meta) {
+        (**self).tagged_parts()
+    }
+}
+
+impl<P: ?Sized, M: Clone> TaggedCmp for (&P, &M) {
+    type Target = P;
+    type Meta = M;
+    fn tagged_parts(&self) -> (&P, M) {
+        (self.0, self.1.clone())
+    }
+}
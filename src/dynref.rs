@@ -0,0 +1,82 @@
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+use super::{Metadata, Tag};
+
+/// A tagged reference to a possibly unsized value, such as a trait object.
+///
+/// A reference to an unsized value already uses its length half for the length or vtable,
+/// so there's no room for the tag in the pointer itself.
+/// Instead, a DynFatRef stores the packed tag in a separate word next to the reference.
+///
+/// ```
+/// use std::fmt::Debug;
+/// use fat_pointer_hack::DynFatRef;
+///
+/// let x = vec![1, 2, 3];
+/// let mut fat: DynFatRef<dyn Debug, char> = DynFatRef::new(&x, 'd');
+/// assert_eq!(format!("{:?}", &*fat), "[1, 2, 3]");
+/// assert_eq!(fat.get_tag(), 'd');
+///
+/// fat.set_tag('e');
+/// assert_eq!(fat.get_tag(), 'e');
+///
+/// let s: DynFatRef<str, usize> = DynFatRef::new("hello", 5);
+/// assert_eq!(s.len(), s.get_tag());
+/// ```
+pub struct DynFatRef<'a, T: ?Sized + 'a, M> {
+    reference: &'a T,
+    tag: usize,
+    phantom: PhantomData<M>,
+}
+
+impl<'a, T: ?Sized + 'a, M: Metadata> DynFatRef<'a, T, M> {
+    /// Makes a DynFatRef from a given reference and a tag.
+    pub fn new(reference: &'a T, tag: M) -> Self {
+        DynFatRef {
+            reference,
+            tag: tag.pack().0,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Turns this DynFatRef back into a regular reference.
+    pub fn to_plain(self) -> &'a T {
+        self.reference
+    }
+
+    /// Returns the tag of this DynFatRef.
+    pub fn get_tag(&self) -> M {
+        M::unpack(Tag(self.tag))
+    }
+
+    /// Sets the tag of this DynFatRef to the given value.
+    pub fn set_tag(&mut self, tag: M) {
+        self.tag = tag.pack().0;
+    }
+}
+
+impl<'a, T: ?Sized + 'a, M> Clone for DynFatRef<'a, T, M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T: ?Sized + 'a, M> Copy for DynFatRef<'a, T, M> {}
+
+impl<'a, T: ?Sized + 'a, M> Deref for DynFatRef<'a, T, M> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        self.reference
+    }
+}
+
+impl<'a, T: ?Sized + fmt::Debug + 'a, M: Metadata + fmt::Debug> fmt::Debug for DynFatRef<'a, T, M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DynFatRef")
+            .field("pointee", &self.reference)
+            .field("tag", &self.get_tag())
+            .finish()
+    }
+}
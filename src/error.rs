@@ -0,0 +1,62 @@
+use core::fmt;
+use core::mem::size_of;
+
+/// The error returned when a tag doesn't fit into a FatRef, see `FatRefExt::try_set_tag`.
+///
+/// Where the offending value is known, like in `pack_to_width`, the error says how wide it was.
+/// Otherwise, `Metadata::try_pack` only tells that it didn't fit, so the error knows just
+/// how wide the metadata type is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TagOverflowError {
+    width: u32,
+    of_type: bool,
+}
+
+impl TagOverflowError {
+    /// An error for a value with `value_width` significant bits.
+    pub(crate) fn new(value_width: u32) -> Self {
+        TagOverflowError {
+            width: value_width,
+            of_type: false,
+        }
+    }
+
+    /// An error for a value of type `M`, whose own width isn't known.
+    pub(crate) fn of<M>() -> Self {
+        TagOverflowError {
+            width: size_of::<M>() as u32 * 8,
+            of_type: true,
+        }
+    }
+
+    /// Returns how many significant bits the value that didn't fit has, if that is known.
+    pub fn value_width(&self) -> Option<u32> {
+        if self.of_type {
+            None
+        } else {
+            Some(self.width)
+        }
+    }
+
+    /// Returns the size in bits of the metadata type whose value didn't fit,
+    /// for errors that don't know the value itself.
+    pub fn type_width(&self) -> Option<u32> {
+        if self.of_type {
+            Some(self.width)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for TagOverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.of_type {
+            write!(f, "a value of a {}-bit type does not fit into the tag", self.width)
+        } else {
+            write!(f, "a {}-bit value does not fit into the tag", self.width)
+        }
+    }
+}
+
+impl core::error::Error for TagOverflowError {}
@@ -0,0 +1,128 @@
+use alloc::sync::Arc;
+use core::ops::Deref;
+use core::ptr;
+
+use super::{fat_ptr, AsFatRef, FatPointee, FatRef, FatRefExt, Metadata};
+
+/// A thread-safe reference-counted pointer to a `P` that carries an arbitrary tag.
+///
+/// Just like with a FatRef, the tag lives in the length half of the (fat) `Arc` pointer.
+/// That means the tag belongs to the handle, not to the shared allocation:
+/// all clones point at the same value, but each of them can carry a different tag.
+///
+/// ```
+/// use fat_pointer_hack::FatArc;
+///
+/// let a = FatArc::new(String::from("shared"), 1);
+/// let mut b = a.clone();
+/// b.set_tag(2);
+///
+/// assert_eq!((a.get_tag(), b.get_tag()), (1, 2));
+/// assert!(std::ptr::eq(&*a, &*b));
+///
+/// let handles: Vec<_> = (0..4)
+///     .map(|i| {
+///         let mut mine = a.clone();
+///         std::thread::spawn(move || {
+///             mine.set_tag(i);
+///             (mine.len(), mine.get_tag())
+///         })
+///     })
+///     .collect();
+/// for (i, handle) in handles.into_iter().enumerate() {
+///     assert_eq!(handle.join().unwrap(), (6, i));
+/// }
+/// assert_eq!(a.get_tag(), 1);
+/// ```
+///
+/// The value is dropped once, when the last handle goes away:
+/// ```
+/// # use fat_pointer_hack::FatArc;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// static DROPS: AtomicUsize = AtomicUsize::new(0);
+/// struct Noisy;
+/// impl Drop for Noisy {
+///     fn drop(&mut self) {
+///         DROPS.fetch_add(1, Ordering::SeqCst);
+///     }
+/// }
+///
+/// let a = FatArc::new(Noisy, 'a');
+/// let mut b = a.clone();
+/// b.set_tag('b');
+/// drop(a);
+/// assert_eq!(DROPS.load(Ordering::SeqCst), 0);
+/// drop(b);
+/// assert_eq!(DROPS.load(Ordering::SeqCst), 1);
+/// ```
+pub struct FatArc<P, M> {
+    inner: Arc<FatPointee<P, M>>,
+}
+
+impl<P, M: Metadata> FatArc<P, M> {
+    /// Moves `value` into a new allocation and makes a FatArc with the given tag to it.
+    pub fn new(value: P, tag: M) -> Self {
+        let thin = Arc::into_raw(Arc::new(value));
+        // SAFETY: `Arc::from_raw` accepts a pointer from `Arc<U>::into_raw` for a sized `U`
+        // with the same size and alignment as the target. `FatPointee<P, M>` is `#[repr(C)]`,
+        // and its only fields after `pointee` are zero-sized with an alignment of 1. So for any
+        // tag, `size_of_val` and `align_of_val` of a `FatPointee<P, M>` are those of a `P`,
+        // the `ArcInner` around it has the same layout, and the value sits at the same offset.
+        // Dropping and deallocating through the fat `Arc` therefore does the same as for `Arc<P>`.
+        FatArc {
+            inner: unsafe { Arc::from_raw(fat_ptr(thin, tag.pack().0)) },
+        }
+    }
+
+    /// Returns the tag of this handle.
+    pub fn get_tag(&self) -> M {
+        self.inner.get_tag()
+    }
+
+    /// Sets the tag of this handle to the given value, other handles keep theirs.
+    pub fn set_tag(&mut self, tag: M) {
+        let raw = tag.pack().0;
+        // SAFETY: the tag doesn't change the layout of a `FatPointee<P, M>`, see `new`,
+        // so the pointer can be given back to `Arc::from_raw` with a different length word.
+        // `ptr::read` moves the `Arc` out, and `ptr::write` puts its replacement back
+        // without dropping the moved-out one, so the reference count stays the same.
+        unsafe {
+            let thin = Arc::into_raw(ptr::read(&self.inner)) as *const P;
+            ptr::write(&mut self.inner, Arc::from_raw(fat_ptr(thin, raw)));
+        }
+    }
+
+    /// Borrows this handle as a FatRef with the same tag.
+    pub fn as_fat_ref(&self) -> FatRef<'_, P, M> {
+        &self.inner
+    }
+}
+
+impl<P, M> Clone for FatArc<P, M> {
+    fn clone(&self) -> Self {
+        FatArc {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<P, M> Deref for FatArc<P, M> {
+    type Target = P;
+    fn deref(&self) -> &P {
+        &self.inner.pointee
+    }
+}
+
+/// Borrows the handle as a FatRef with the same tag.
+///
+/// ```
+/// # use fat_pointer_hack::{AsFatRef, FatArc, FatRefExt};
+/// let arc = FatArc::new(5, 'a');
+/// assert_eq!(arc.fat().get_tag(), 'a');
+/// ```
+impl<P, M: Metadata> AsFatRef<P, M> for FatArc<P, M> {
+    fn fat(&self) -> FatRef<'_, P, M> {
+        self.as_fat_ref()
+    }
+}
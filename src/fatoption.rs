@@ -0,0 +1,75 @@
+use core::mem::size_of;
+
+use super::{FatRef, FatRefExt, FatRefMut, FatRefMutExt, Metadata};
+
+/// Helpers for optional fat references.
+///
+/// FatRefs and FatRefMuts are references, so `Option` stores `None` as a null data pointer
+/// and an optional fat reference is still two words wide. This is guaranteed by the language
+/// and checked at compile time.
+/// ```
+/// # use fat_pointer_hack::{FatOption, FatRef, FatRefExt};
+/// use std::mem::size_of;
+///
+/// assert_eq!(size_of::<Option<FatRef<u8, usize>>>(), 2 * size_of::<usize>());
+///
+/// let x = 5;
+/// let some: Option<FatRef<i32, char>> = FatOption::from_option(Some(&x), 'x');
+/// assert_eq!(some.map(|fat| fat.get_tag()), Some('x'));
+/// assert_eq!(some.as_option(), Some(&5));
+///
+/// let none: Option<FatRef<i32, char>> = FatOption::from_option(None, 'x');
+/// assert!(none.is_none());
+/// assert_eq!(none.as_option(), None);
+/// ```
+pub trait FatOption: Sized {
+    type Thin;
+    type Meta;
+    fn from_option(thin: Option<Self::Thin>, tag: Self::Meta) -> Self;
+    #[allow(clippy::wrong_self_convention)]
+    fn as_option(self) -> Option<Self::Thin>;
+}
+
+impl<'a, P, M: 'a + Metadata> FatOption for Option<FatRef<'a, P, M>> {
+    type Thin = &'a P;
+    type Meta = M;
+
+    /// Tags the reference, if there is one. Otherwise, the tag is dropped.
+    fn from_option(thin: Option<&'a P>, tag: M) -> Self {
+        thin.map(|thin| FatRef::from_ref(thin, tag))
+    }
+
+    /// Drops the tag, keeping the reference if there is one.
+    fn as_option(self) -> Option<&'a P> {
+        self.map(FatRefExt::to_plain)
+    }
+}
+
+impl<'a, P, M: 'a + Metadata> FatOption for Option<FatRefMut<'a, P, M>> {
+    type Thin = &'a mut P;
+    type Meta = M;
+
+    /// Tags the mutable reference, if there is one. Otherwise, the tag is dropped.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{FatOption, FatRefMut, FatRefMutExt};
+    /// let mut x = 5;
+    /// let fat: Option<FatRefMut<i32, usize>> = FatOption::from_option(Some(&mut x), 3);
+    /// if let Some(n) = fat.as_option() {
+    ///     *n += 1;
+    /// }
+    /// assert_eq!(x, 6);
+    /// ```
+    fn from_option(thin: Option<&'a mut P>, tag: M) -> Self {
+        thin.map(|thin| FatRefMut::from_ref_mut(thin, tag))
+    }
+
+    /// Drops the tag, keeping the mutable reference if there is one.
+    fn as_option(self) -> Option<&'a mut P> {
+        self.map(FatRefMutExt::to_plain_mut)
+    }
+}
+
+const _: () = assert!(size_of::<Option<FatRef<'static, u8, usize>>>() == 2 * size_of::<usize>());
+const _: () = assert!(size_of::<Option<FatRefMut<'static, u8, usize>>>() == 2 * size_of::<usize>());
+const _: () = assert!(size_of::<Option<FatRef<'static, [u64; 4], char>>>() == 2 * size_of::<usize>());
@@ -0,0 +1,150 @@
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+use core::marker::PhantomData;
+use core::slice;
+
+use super::{fat_ptr, FatRef, Metadata};
+
+/// A growable buffer of `P`s where every element carries its own tag.
+///
+/// The values are stored densely in a `Vec<P>` and the packed tags in a parallel `Vec<usize>`,
+/// so adding tags doesn't change the layout of the values.
+/// Elements are handed out as `FatRef`s that are built on the fly from the two.
+///
+/// ```
+/// use fat_pointer_hack::{FatVec, FatRefExt};
+///
+/// let mut v = FatVec::new();
+/// v.push("foo", 'a');
+/// v.push("bar", 'b');
+///
+/// let bar = v.get(1).unwrap();
+/// assert_eq!(*bar.to_plain(), "bar");
+/// assert_eq!(bar.get_tag(), 'b');
+///
+/// assert_eq!(v.iter().map(|r| r.get_tag()).collect::<Vec<_>>(), ['a', 'b']);
+/// ```
+pub struct FatVec<P, M> {
+    values: Vec<P>,
+    tags: Vec<usize>,
+    phantom: PhantomData<M>,
+}
+
+impl<P, M: Metadata> FatVec<P, M> {
+    /// Makes a new, empty FatVec.
+    pub fn new() -> Self {
+        FatVec {
+            values: Vec::new(),
+            tags: Vec::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Appends a value with the given tag.
+    pub fn push(&mut self, value: P, tag: M) {
+        self.values.push(value);
+        self.tags.push(tag.pack().0);
+    }
+
+    /// Returns a tagged reference to the element at `index`, or `None` if it is out of bounds.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{FatVec, FatRefExt};
+    /// let mut v = FatVec::new();
+    /// v.push(10u8, 1);
+    /// v.push(20u8, 2);
+    ///
+    /// assert_eq!(v.get(0).map(|r| (*r.to_plain(), r.get_tag())), Some((10, 1)));
+    /// assert_eq!(v.get(1).map(|r| (*r.to_plain(), r.get_tag())), Some((20, 2)));
+    /// assert!(v.get(2).is_none());
+    /// ```
+    pub fn get(&self, index: usize) -> Option<FatRef<'_, P, M>> {
+        let value = self.values.get(index)?;
+        Some(unsafe { &*fat_ptr(value, self.tags[index]) })
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Iterates over tagged references to all elements, in order.
+    pub fn iter(&self) -> FatVecIter<'_, P, M> {
+        FatVecIter {
+            values: self.values.iter(),
+            tags: self.tags.iter(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Replaces every tag with the result of calling `f` with the index and the element.
+    ///
+    /// Only the tags are rewritten, the elements stay where they are.
+    /// ```
+    /// # use fat_pointer_hack::{FatVec, FatRefExt};
+    /// let mut v: FatVec<&str, usize> = vec![("pear", 0), ("apple", 0), ("fig", 0)].into_iter().collect();
+    /// let first = v.get(0).unwrap().as_ptr();
+    ///
+    /// let mut sorted: Vec<&str> = v.iter().map(|r| *r.to_plain()).collect();
+    /// sorted.sort();
+    /// v.retag_all(|_, name| sorted.iter().position(|s| s == name).unwrap());
+    ///
+    /// assert_eq!(v.iter().map(|r| r.get_tag()).collect::<Vec<_>>(), [2, 0, 1]);
+    /// assert_eq!(v.get(0).unwrap().as_ptr(), first);
+    /// ```
+    pub fn retag_all<F: FnMut(usize, &P) -> M>(&mut self, mut f: F) {
+        for (index, (value, tag)) in self.values.iter().zip(&mut self.tags).enumerate() {
+            *tag = f(index, value).pack().0;
+        }
+    }
+}
+
+impl<P, M: Metadata> Default for FatVec<P, M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Collects `(value, tag)` pairs into a FatVec.
+///
+/// ```
+/// # use fat_pointer_hack::{FatVec, FatRefExt};
+/// let v: FatVec<u32, usize> = (10..13u32).map(|n| (n, n as usize % 10)).collect();
+/// assert_eq!(v.len(), 3);
+/// assert_eq!(v.iter().map(|r| (*r.to_plain(), r.get_tag())).collect::<Vec<_>>(), [(10, 0), (11, 1), (12, 2)]);
+/// ```
+impl<P, M: Metadata> FromIterator<(P, M)> for FatVec<P, M> {
+    fn from_iter<I: IntoIterator<Item = (P, M)>>(iter: I) -> Self {
+        let mut v = Self::new();
+        for (value, tag) in iter {
+            v.push(value, tag);
+        }
+        v
+    }
+}
+
+/// An iterator over the elements of a `FatVec`, created by `FatVec::iter`.
+pub struct FatVecIter<'a, P: 'a, M: 'a> {
+    values: slice::Iter<'a, P>,
+    tags: slice::Iter<'a, usize>,
+    phantom: PhantomData<M>,
+}
+
+impl<'a, P, M: Metadata> Iterator for FatVecIter<'a, P, M> {
+    type Item = FatRef<'a, P, M>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.values.next()?;
+        let tag = *self.tags.next()?;
+        Some(unsafe { &*fat_ptr(value, tag) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.values.size_hint()
+    }
+}
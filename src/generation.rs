@@ -0,0 +1,107 @@
+use super::{FatRef, FatRefExt, FatRefMut, FatRefMutExt, Metadata, Tag};
+
+/// A slot index together with a generation counter, the building block of generational arenas.
+///
+/// A reference tagged with a Generation can be checked against the current generation of
+/// its slot to detect that it has become stale.
+/// ```
+/// # use fat_pointer_hack::{Generation, GenerationRefExt, RefExt};
+/// let value = "slot contents";
+/// let mut fat = (&value).tag(Generation::new(3, 0));
+/// let stale = fat;
+///
+/// fat.bump_generation();
+/// assert_eq!((fat.slot(), fat.generation()), (3, 1));
+/// assert_eq!((stale.slot(), stale.generation()), (3, 0));
+///
+/// let mut max = (&value).tag(Generation::new(7, u32::MAX));
+/// max.bump_generation();
+/// assert_eq!((max.slot(), max.generation()), (7, 0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Generation {
+    generation: u32,
+    slot: u16,
+}
+
+impl Generation {
+    /// Makes a Generation for the given slot.
+    pub fn new(slot: u16, generation: u32) -> Self {
+        Generation { generation, slot }
+    }
+
+    /// Returns the generation counter.
+    pub fn generation(self) -> u32 {
+        self.generation
+    }
+
+    /// Returns the slot index.
+    pub fn slot(self) -> u16 {
+        self.slot
+    }
+
+    /// Returns the next generation of the same slot, wrapping around on overflow.
+    pub fn bumped(self) -> Self {
+        Generation::new(self.slot, self.generation.wrapping_add(1))
+    }
+}
+
+/// The slot is stored in the low 16 bits of the tag, the generation in the 32 bits above.
+#[cfg_attr(trybuild, diagnostic::do_not_recommend)]
+impl Metadata for Generation {
+    fn pack(self) -> Tag {
+        Tag(self.slot as usize | (self.generation as usize) << 16)
+    }
+    fn unpack(val: Tag) -> Self {
+        Generation::new(val.0 as u16, (val.0 >> 16) as u32)
+    }
+}
+
+/// An extension trait for fat references tagged with a `Generation`.
+pub trait GenerationRefExt {
+    fn generation(&self) -> u32;
+    fn slot(&self) -> u16;
+    fn bump_generation(&mut self);
+}
+
+impl<'a, P> GenerationRefExt for FatRef<'a, P, Generation> {
+    /// Returns the generation counter of the tag.
+    fn generation(&self) -> u32 {
+        self.get_tag().generation()
+    }
+
+    /// Returns the slot index of the tag.
+    fn slot(&self) -> u16 {
+        self.get_tag().slot()
+    }
+
+    /// Moves the tag on to the next generation, keeping the slot.
+    fn bump_generation(&mut self) {
+        self.update_tag(Generation::bumped);
+    }
+}
+
+impl<'a, P> GenerationRefExt for FatRefMut<'a, P, Generation> {
+    /// Returns the generation counter of the tag.
+    fn generation(&self) -> u32 {
+        self.get_tag().generation()
+    }
+
+    /// Returns the slot index of the tag.
+    fn slot(&self) -> u16 {
+        self.get_tag().slot()
+    }
+
+    /// Moves the tag on to the next generation, keeping the slot.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{Generation, GenerationRefExt, RefExt};
+    /// let mut x = 5;
+    /// let mut fat = (&mut x).tag(Generation::new(1, 41));
+    /// fat.bump_generation();
+    /// assert_eq!((fat.slot(), fat.generation()), (1, 42));
+    /// ```
+    fn bump_generation(&mut self) {
+        self.update_tag(Generation::bumped);
+    }
+}
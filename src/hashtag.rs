@@ -0,0 +1,65 @@
+use core::hash::{Hash, Hasher};
+
+use super::{FatRef, FatRefMut, RefExt};
+
+/// Hashes `value` with a fresh `H`, keeping the low bits that fit into a tag.
+pub(crate) fn truncated_hash<H: Hasher + Default, T: Hash + ?Sized>(value: &T) -> usize {
+    let mut hasher = H::default();
+    value.hash(&mut hasher);
+    hasher.finish() as usize
+}
+
+/// An extension trait for tagging references with a hash of their pointee.
+///
+/// The hash is truncated to `usize::BITS` bits. Check it later with `verify_tag`.
+/// A matching hash is strong evidence, but no proof, that the pointee is unchanged:
+/// different values can have the same hash.
+///
+/// The hasher is a type parameter of the method. Methods can't have
+/// default type parameters, so it always has to be named.
+/// ```
+/// # use fat_pointer_hack::{FatRefExt, HashTagExt};
+/// use std::collections::hash_map::DefaultHasher;
+///
+/// let entry = String::from("cached");
+/// let mut fat = (&entry).tag_hash::<DefaultHasher>();
+/// assert!(fat.verify_tag::<DefaultHasher>());
+///
+/// fat.set_tag(fat.get_tag() ^ 1);
+/// assert!(!fat.verify_tag::<DefaultHasher>());
+/// ```
+pub trait HashTagExt {
+    type Output;
+    fn tag_hash<H: Hasher + Default>(self) -> Self::Output;
+}
+
+impl<'a, T: Hash> HashTagExt for &'a T {
+    type Output = FatRef<'a, T, usize>;
+
+    /// Tags this reference with the truncated hash of its pointee.
+    fn tag_hash<H: Hasher + Default>(self) -> FatRef<'a, T, usize> {
+        self.tag(truncated_hash::<H, T>(self))
+    }
+}
+
+impl<'a, T: Hash> HashTagExt for &'a mut T {
+    type Output = FatRefMut<'a, T, usize>;
+
+    /// Tags this mutable reference with the truncated hash of its pointee.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{FatRefMutExt, HashTagExt};
+    /// use std::collections::hash_map::DefaultHasher;
+    ///
+    /// let mut entry = vec![1, 2, 3];
+    /// let mut fat = (&mut entry).tag_hash::<DefaultHasher>();
+    /// assert!(fat.verify_tag::<DefaultHasher>());
+    ///
+    /// fat.reborrow().to_plain_mut().push(4);
+    /// assert!(!fat.verify_tag::<DefaultHasher>());
+    /// ```
+    fn tag_hash<H: Hasher + Default>(self) -> FatRefMut<'a, T, usize> {
+        let hash = truncated_hash::<H, T>(self);
+        self.tag(hash)
+    }
+}
@@ -0,0 +1,149 @@
+use super::{Metadata, RefExt};
+
+/// An extension trait for tagging the references yielded by an iterator.
+///
+/// It works for iterators over `&T` as well as `&mut T`,
+/// yielding `FatRef`s or `FatRefMut`s respectively.
+///
+/// ```
+/// # use fat_pointer_hack::{FatRefExt, FatRefMutExt, TagIterExt};
+/// let words = ["a", "bcd", "ef"];
+/// let tagged: Vec<_> = words.iter().tag_with(|w| w.len()).map(|fat| fat.into_parts()).collect();
+/// assert_eq!(tagged, [(&"a", 1), (&"bcd", 3), (&"ef", 2)]);
+///
+/// let mut numbers = vec![10, 20, 30];
+/// for fat in numbers.iter_mut().tag_enumerate() {
+///     *fat.as_mut() += fat.get_tag();
+/// }
+/// assert_eq!(numbers, [10, 21, 32]);
+/// ```
+pub trait TagIterExt: Iterator + Sized {
+    fn tag_with<M, F>(self, f: F) -> TagWith<Self, F>
+    where
+        M: Metadata,
+        Self::Item: RefExt<M>,
+        F: FnMut(&Self::Item) -> M;
+    fn tag_enumerate(self) -> TagEnumerate<Self>
+    where
+        Self::Item: RefExt<usize>;
+}
+
+impl<I: Iterator> TagIterExt for I {
+    /// Tags every reference with the result of calling `f` on it.
+    fn tag_with<M, F>(self, f: F) -> TagWith<Self, F>
+    where
+        M: Metadata,
+        Self::Item: RefExt<M>,
+        F: FnMut(&Self::Item) -> M,
+    {
+        TagWith { iter: self, f }
+    }
+
+    /// Tags every reference with its index.
+    ///
+    /// The indices are counted from the front, even when iterating from the back:
+    /// ```
+    /// # use fat_pointer_hack::{FatRefExt, TagIterExt};
+    /// let tags: Vec<usize> = [5, 6, 7].iter().tag_enumerate().rev().map(|fat| fat.get_tag()).collect();
+    /// assert_eq!(tags, [2, 1, 0]);
+    /// ```
+    fn tag_enumerate(self) -> TagEnumerate<Self>
+    where
+        Self::Item: RefExt<usize>,
+    {
+        TagEnumerate { iter: self, count: 0 }
+    }
+}
+
+/// An iterator that tags each reference with a computed value, see `TagIterExt::tag_with`.
+pub struct TagWith<I, F> {
+    iter: I,
+    f: F,
+}
+
+impl<M, I, F> Iterator for TagWith<I, F>
+where
+    M: Metadata,
+    I: Iterator,
+    I::Item: RefExt<M>,
+    F: FnMut(&I::Item) -> M,
+{
+    type Item = <I::Item as RefExt<M>>::Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        let tag = (self.f)(&item);
+        Some(item.tag(tag))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<M, I, F> DoubleEndedIterator for TagWith<I, F>
+where
+    M: Metadata,
+    I: DoubleEndedIterator,
+    I::Item: RefExt<M>,
+    F: FnMut(&I::Item) -> M,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next_back()?;
+        let tag = (self.f)(&item);
+        Some(item.tag(tag))
+    }
+}
+
+impl<M, I, F> ExactSizeIterator for TagWith<I, F>
+where
+    M: Metadata,
+    I: ExactSizeIterator,
+    I::Item: RefExt<M>,
+    F: FnMut(&I::Item) -> M,
+{
+}
+
+/// An iterator that tags each reference with its index, see `TagIterExt::tag_enumerate`.
+pub struct TagEnumerate<I> {
+    iter: I,
+    count: usize,
+}
+
+impl<I> Iterator for TagEnumerate<I>
+where
+    I: Iterator,
+    I::Item: RefExt<usize>,
+{
+    type Item = <I::Item as RefExt<usize>>::Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        let index = self.count;
+        self.count += 1;
+        Some(item.tag(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I> DoubleEndedIterator for TagEnumerate<I>
+where
+    I: DoubleEndedIterator + ExactSizeIterator,
+    I::Item: RefExt<usize>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next_back()?;
+        let index = self.count + self.iter.len();
+        Some(item.tag(index))
+    }
+}
+
+impl<I> ExactSizeIterator for TagEnumerate<I>
+where
+    I: ExactSizeIterator,
+    I::Item: RefExt<usize>,
+{
+}
@@ -0,0 +1,126 @@
+use super::{Metadata, Tag};
+
+/// Two values packed side by side into one tag, e.g. a `(row, col)` coordinate.
+///
+/// The first value is stored in the low half of the tag.
+/// ```
+/// # use fat_pointer_hack::{RefExt, FatRefExt, Pair};
+/// let cell = "cell";
+/// for &(row, col) in &[(0, 0), (u32::MAX, 0), (0, u32::MAX), (u32::MAX, u32::MAX), (1, u32::MAX - 1)] {
+///     let fat = (&cell).tag(Pair(row, col));
+///     assert_eq!(fat.get_tag(), Pair(row, col));
+///     assert_eq!((fat.get_tag().first(), fat.get_tag().second()), (row, col));
+/// }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pair<T>(pub T, pub T);
+
+impl<T: Copy> Pair<T> {
+    /// Returns the first value, stored in the low half of the tag.
+    pub fn first(self) -> T {
+        self.0
+    }
+
+    /// Returns the second value, stored in the high half of the tag.
+    pub fn second(self) -> T {
+        self.1
+    }
+}
+
+#[cfg_attr(trybuild, diagnostic::do_not_recommend)]
+impl Metadata for Pair<u32> {
+    fn pack(self) -> Tag {
+        (self.0, self.1).pack()
+    }
+    fn unpack(val: Tag) -> Self {
+        let (first, second) = <(u32, u32)>::unpack(val);
+        Pair(first, second)
+    }
+}
+
+/// Four values packed side by side into one tag.
+///
+/// Lane 0 is stored in the lowest 16 bits of the tag, lane 3 in the highest.
+/// ```
+/// # use fat_pointer_hack::{RefExt, FatRefExt, Quad};
+/// let x = 5;
+/// for lane in 0..4 {
+///     let mut lanes = [0; 4];
+///     lanes[lane] = u16::MAX;
+///     let fat = (&x).tag(Quad(lanes));
+///     assert_eq!(fat.get_tag(), Quad(lanes));
+///     assert_eq!(fat.get_tag().lane(lane), u16::MAX);
+///     assert!((0..4).filter(|&other| other != lane).all(|other| fat.get_tag().lane(other) == 0));
+/// }
+/// let full = (&x).tag(Quad([u16::MAX, 1, u16::MAX - 1, 0]));
+/// assert_eq!(full.get_tag().lanes(), [u16::MAX, 1, u16::MAX - 1, 0]);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Quad<T>(pub [T; 4]);
+
+impl<T: Copy> Quad<T> {
+    /// Returns the value in the given lane.
+    ///
+    /// Panics if `lane` is not less than 4.
+    pub fn lane(self, lane: usize) -> T {
+        self.0[lane]
+    }
+
+    /// Returns all four lanes.
+    pub fn lanes(self) -> [T; 4] {
+        self.0
+    }
+}
+
+#[cfg_attr(trybuild, diagnostic::do_not_recommend)]
+impl Metadata for Quad<u16> {
+    fn pack(self) -> Tag {
+        Tag(self
+            .0
+            .iter()
+            .enumerate()
+            .fold(0, |tag, (lane, &value)| tag | (value as usize) << (16 * lane)))
+    }
+    fn unpack(val: Tag) -> Self {
+        let mut lanes = [0; 4];
+        for (lane, value) in lanes.iter_mut().enumerate() {
+            *value = (val.0 >> (16 * lane)) as u16;
+        }
+        Quad(lanes)
+    }
+}
+
+/// Two chars packed into one tag, e.g. a digraph.
+///
+/// The first char is stored in the low half of the tag. A half that isn't a valid char,
+/// which can only happen if the raw tag was set by hand, unpacks to `char::REPLACEMENT_CHARACTER`.
+/// ```
+/// # use fat_pointer_hack::{RefExt, FatRefExt, CharPair};
+/// let token = "token";
+/// for &(first, second) in &[('c', 'h'), ('\0', char::MAX), ('\u{D7FF}', '\u{E000}'), ('♥', '🦀')] {
+///     let fat = (&token).tag(CharPair(first, second));
+///     assert_eq!(fat.get_tag(), CharPair(first, second));
+/// }
+///
+/// let mut fat = (&token).tag(CharPair('a', 'b'));
+/// fat.set_raw_tag(0xD800 | ('b' as usize) << 32);
+/// assert_eq!(fat.get_tag(), CharPair(char::REPLACEMENT_CHARACTER, 'b'));
+/// fat.set_raw_tag('a' as usize | 0xDFFF << 32);
+/// assert_eq!(fat.get_tag(), CharPair('a', char::REPLACEMENT_CHARACTER));
+/// fat.set_raw_tag(0x11_0000 | 0xFFFF_FFFF << 32);
+/// assert_eq!(fat.get_tag(), CharPair(char::REPLACEMENT_CHARACTER, char::REPLACEMENT_CHARACTER));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CharPair(pub char, pub char);
+
+#[cfg_attr(trybuild, diagnostic::do_not_recommend)]
+impl Metadata for CharPair {
+    fn pack(self) -> Tag {
+        (self.0 as u32, self.1 as u32).pack()
+    }
+    fn unpack(val: Tag) -> Self {
+        let (first, second) = <(u32, u32)>::unpack(val);
+        let decode = |bits| char::from_u32(bits).unwrap_or(char::REPLACEMENT_CHARACTER);
+        CharPair(decode(first), decode(second))
+    }
+}
@@ -17,8 +17,9 @@
 //! // However, it is now two pointers wide
 //! assert_eq!(std::mem::size_of_val(&fat_ref), 2 * std::mem::size_of::<usize>());
 //! 
-//! // To actually use the reference, you need to .as_ref() it:
-//! assert_eq!(fat_ref.as_ref(), &5);
+//! // It derefs to the pointee, or you can .as_ref() it:
+//! assert_eq!(*fat_ref.as_ref(), 5);
+//! assert_eq!(**fat_ref, 5);
 //!
 //! // You can access the tag
 //! assert_eq!(fat_ref.get_tag(), 9001);
@@ -64,6 +65,7 @@
 //! let shared_fat_ref = (&x).tag(0);
 //! 
 //! x.push(4); // Doesn't compile - x is borrowed!
+//! shared_fat_ref.get_tag();
 //! ```
 //!
 //! ## Why?
@@ -120,6 +122,46 @@
 
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "arbitrary")]
+extern crate arbitrary;
+#[cfg(feature = "half")]
+extern crate half;
+#[cfg(feature = "bytemuck")]
+extern crate bytemuck;
+
+mod macros;
+
+/// Builds a fat pointer to `thin` whose length word is `raw`.
+const fn fat_ptr<P, M>(thin: *const P, raw: usize) -> *const FatPointee<P, M> {
+    core::ptr::slice_from_raw_parts(thin as *const (), raw) as *const FatPointee<P, M>
+}
+
+/// Packs `tag` into a raw length word, or returns an error if it doesn't fit.
+fn try_pack_raw<M: Metadata>(tag: M) -> Result<usize, TagOverflowError> {
+    tag.try_pack().map(|tag| tag.0).ok_or_else(TagOverflowError::of::<M>)
+}
+
+/// Builds a mutable fat pointer to `thin` whose length word is `raw`.
+fn fat_ptr_mut<P, M>(thin: *mut P, raw: usize) -> *mut FatPointee<P, M> {
+    core::ptr::slice_from_raw_parts_mut(thin as *mut (), raw) as *mut FatPointee<P, M>
+}
+
+// `len_word_index` and `FatRefMutExt::tag_word_mut` treat fat references as two words.
+const _: () = assert!(core::mem::size_of::<FatRef<'static, (), ()>>() == 2 * core::mem::size_of::<usize>());
+
+/// Finds out which word of a fat pointer holds the length, the layout of fat pointers being unspecified.
+fn len_word_index() -> usize {
+    let probe = core::ptr::slice_from_raw_parts(core::ptr::null::<()>(), 1);
+    let words: [usize; 2] = unsafe { core::mem::transmute(probe) };
+    if words[0] == 1 {
+        0
+    } else {
+        1
+    }
+}
+
 /// A fat reference to a `P` that carries a `&P` and an arbitrary usize tag.
 pub type FatRef<'a, P, M> = &'a FatPointee<P, M>;
 
@@ -130,6 +172,17 @@ pub type FatRefMut<'a, P, M> = &'a mut FatPointee<P, M>;
 ///
 /// Not used directly, but `FatRef<P>` and `FatRefMut<P>` point at this
 /// and since they're just type aliases, this has to be public as well.
+///
+/// Because the metadata type is part of the pointee type, a `FatRef<'a, P, M>` requires
+/// `M: 'a` like any other `&'a` reference would, even though the tag is stored by value:
+/// ```compile_fail
+/// # use fat_pointer_hack::FatRef;
+/// # use std::marker::PhantomData;
+/// struct Marker<'b>(PhantomData<&'b ()>);
+/// struct Holder<'b> {
+///     fat: FatRef<'static, u8, Marker<'b>>,
+/// }
+/// ```
 #[repr(C)]
 pub struct FatPointee<P, M> {
     pointee: P,
@@ -137,16 +190,206 @@ pub struct FatPointee<P, M> {
     unsize: [()],
 }
 
+/// Checks whether FatRefs and FatRefMuts to a `P` are two words wide, a pointer and a tag.
+///
+/// That's always the case, and this crate checks it at compile time for a few types.
+/// The data half points at the pointee, but the order of the two halves is unspecified:
+/// ```
+/// # use fat_pointer_hack::{layout_is_fat, FatRef, FatRefMut, RefExt};
+/// use std::mem::{align_of, size_of, transmute};
+///
+/// const _: () = assert!(layout_is_fat::<String, char>());
+/// assert!(layout_is_fat::<[u64; 32], usize>());
+/// assert_eq!(size_of::<FatRef<u8, usize>>(), 2 * size_of::<usize>());
+/// assert_eq!(size_of::<FatRefMut<u8, usize>>(), 2 * size_of::<usize>());
+/// assert_eq!(align_of::<FatRef<u8, usize>>(), align_of::<usize>());
+///
+/// let x = 5u8;
+/// let words: [usize; 2] = unsafe { transmute((&x).tag(7)) };
+/// assert!(words.contains(&(&x as *const u8 as usize)));
+/// assert!(words.contains(&7));
+/// ```
+pub const fn layout_is_fat<P, M>() -> bool {
+    use core::mem::{align_of, size_of};
+    size_of::<*const FatPointee<P, M>>() == 2 * size_of::<usize>()
+        && size_of::<*mut FatPointee<P, M>>() == 2 * size_of::<usize>()
+        && align_of::<*const FatPointee<P, M>>() == align_of::<usize>()
+}
+
+const _: () = assert!(layout_is_fat::<u8, usize>());
+const _: () = assert!(layout_is_fat::<(), char>());
+const _: () = assert!(layout_is_fat::<[u64; 4], Option<core::num::NonZeroUsize>>());
+
+/// The packed form of a tag, as produced by `Metadata::pack`.
 pub struct Tag(usize);
 
+impl Tag {
+    /// Returns the raw bits of this Tag.
+    pub fn into_raw(self) -> usize {
+        self.0
+    }
+}
+
 /// A trait for types that can be used as a Tag.
+///
+/// # Unpacking arbitrary bits
+///
+/// `unpack` must accept every bit pattern without causing undefined behavior,
+/// not just the ones its own `pack` produces. Safe methods like `FatRefExt::cast_meta`
+/// can put any bits into a tag, and the tag of a FatRef is unpacked whenever it is read.
+/// Bits that don't make sense for the type should unpack to some fallback value instead,
+/// like `char` does with `char::REPLACEMENT_CHARACTER`:
+/// ```
+/// # use fat_pointer_hack::{RefExt, FatRefExt};
+/// let x = 5;
+/// assert_eq!((&x).tag(0xD800usize).cast_meta::<char>().get_tag(), '\u{FFFD}');
+/// ```
+///
+/// # Round trips
+///
+/// Packing and unpacking a value has to give back the same value.
+/// For floats that means the same bits, NaN payloads included:
+/// ```
+/// # extern crate proptest;
+/// # extern crate fat_pointer_hack;
+/// use std::num::NonZeroUsize;
+/// use proptest::prelude::*;
+/// use proptest::test_runner::TestRunner;
+/// use fat_pointer_hack::Metadata;
+///
+/// fn round_trip<M: Metadata + Clone>(value: &M) -> M {
+///     M::unpack(value.clone().pack())
+/// }
+///
+/// # fn main() {
+/// let mut runner = TestRunner::default();
+/// runner.run(&any::<usize>(), |x| { prop_assert_eq!(round_trip(&x), x); Ok(()) }).unwrap();
+/// runner.run(&any::<[u8; std::mem::size_of::<usize>()]>(), |x| { prop_assert_eq!(round_trip(&x), x); Ok(()) }).unwrap();
+/// runner.run(&any::<Option<NonZeroUsize>>(), |x| { prop_assert_eq!(round_trip(&x), x); Ok(()) }).unwrap();
+/// runner.run(&any::<char>(), |x| { prop_assert_eq!(round_trip(&x), x); Ok(()) }).unwrap();
+/// runner.run(&any::<(u32, u32)>(), |x| { prop_assert_eq!(round_trip(&x), x); Ok(()) }).unwrap();
+/// runner.run(&any::<u64>(), |bits| {
+///     let x = f64::from_bits(bits);
+///     prop_assert_eq!(round_trip(&x).to_bits(), bits);
+///     Ok(())
+/// }).unwrap();
+/// runner.run(&any::<u32>(), |bits| {
+///     let x = f32::from_bits(bits);
+///     prop_assert_eq!(round_trip(&x).to_bits(), bits);
+///     Ok(())
+/// }).unwrap();
+///
+/// assert_eq!(round_trip(&f64::NAN).to_bits(), f64::NAN.to_bits());
+/// assert_ne!(round_trip(&f64::NAN), f64::NAN);
+/// # }
+/// ```
+///
+/// Some impls only exist on targets whose `usize` is wide enough for the value:
+/// `f32`, `char`, `Ipv4Addr` and `BitEqFloat<f32>` need at least 32-bit pointers,
+/// `f64`, `BitEqFloat<f64>`, `(u32, u32)`, `Pair`, `Quad`, `CharPair` and `Generation` need 64-bit pointers.
+// The UI tests build with `--cfg trybuild`, which marks the impls as `do_not_recommend`,
+// so that the pinned diagnostics don't list every type that implements this trait.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be used as a tag",
+    note = "`f32`, `char`, `Ipv4Addr` and `BitEqFloat<f32>` can only be tags on targets with at least 32-bit pointers, `f64`, `BitEqFloat<f64>`, `(u32, u32)`, `Pair`, `Quad`, `CharPair` and `Generation` only with 64-bit pointers"
+)]
 pub trait Metadata: Sized {
     /// Stuff this value into a Tag.
     fn pack(self) -> Tag;
     /// Unpack this value from a Tag.
-    fn unpack(Tag) -> Self;
+    ///
+    /// This must not cause undefined behavior for any Tag, see the trait docs.
+    fn unpack(tag: Tag) -> Self;
+    /// Stuff this value into a Tag, or return `None` if that would lose information.
+    ///
+    /// Only types that can be wider than a `usize` need to override this.
+    fn try_pack(self) -> Option<Tag> {
+        Some(self.pack())
+    }
+    /// Checks that unpacking this value after packing it gives back an equal value.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{Metadata, Tag};
+    /// # use std::num::NonZeroUsize;
+    /// assert!(7usize.verify());
+    /// assert!('♥'.verify());
+    /// assert!(NonZeroUsize::new(3).verify());
+    /// assert!(1.5f64.verify());
+    ///
+    /// // This impl forgets the top bit.
+    /// #[derive(Clone, PartialEq)]
+    /// struct Lossy(usize);
+    /// impl Metadata for Lossy {
+    ///     fn pack(self) -> Tag {
+    ///         (self.0 & (usize::MAX >> 1)).pack()
+    ///     }
+    ///     fn unpack(tag: Tag) -> Self {
+    ///         Lossy(usize::unpack(tag))
+    ///     }
+    /// }
+    /// assert!(Lossy(1).verify());
+    /// assert!(!Lossy(usize::MAX).verify());
+    /// ```
+    fn verify(self) -> bool
+    where
+        Self: PartialEq + Clone,
+    {
+        Self::unpack(self.clone().pack()) == self
+    }
+    /// Checks that packing this value again after a round trip gives the same bits.
+    ///
+    /// Unlike `verify`, this doesn't need `PartialEq`, and it works for floats,
+    /// where a NaN doesn't compare equal to itself:
+    /// ```
+    /// # use fat_pointer_hack::Metadata;
+    /// assert!(!f64::NAN.verify());
+    /// assert!(f64::NAN.verify_packed());
+    /// assert!((-0.0f32).verify_packed());
+    /// ```
+    fn verify_packed(self) -> bool {
+        let raw = self.pack().0;
+        Self::unpack(Tag(raw)).pack().0 == raw
+    }
 }
 
+/// Panics if `value` doesn't survive a round trip through a Tag, for testing Metadata impls.
+///
+/// ```
+/// # use fat_pointer_hack::{assert_metadata_roundtrip, Generation, SmallStr, TypeTag};
+/// assert_metadata_roundtrip(usize::MAX);
+/// assert_metadata_roundtrip('a');
+/// assert_metadata_roundtrip((1u32, u32::MAX));
+/// assert_metadata_roundtrip(std::time::Duration::from_millis(5));
+/// assert_metadata_roundtrip(TypeTag(9));
+/// assert_metadata_roundtrip(SmallStr::new("tag").unwrap());
+/// assert_metadata_roundtrip(Generation::new(1, 2));
+/// ```
+///
+/// ```should_panic
+/// # use fat_pointer_hack::{assert_metadata_roundtrip, Metadata, Tag};
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct Forgetful(usize);
+/// impl Metadata for Forgetful {
+///     fn pack(self) -> Tag {
+///         0usize.pack()
+///     }
+///     fn unpack(tag: Tag) -> Self {
+///         Forgetful(usize::unpack(tag))
+///     }
+/// }
+/// assert_metadata_roundtrip(Forgetful(1));
+/// ```
+pub fn assert_metadata_roundtrip<M: Metadata + PartialEq + Clone + Debug>(value: M) {
+    let back = M::unpack(value.clone().pack());
+    assert!(
+        back == value,
+        "metadata round trip failed: {:?} unpacked as {:?}",
+        value,
+        back
+    );
+}
+
+#[cfg_attr(trybuild, diagnostic::do_not_recommend)]
 impl Metadata for usize {
     fn pack(self) -> Tag {
         Tag(self)
@@ -156,16 +399,41 @@ impl Metadata for usize {
     }
 }
 
+#[cfg_attr(trybuild, diagnostic::do_not_recommend)]
 impl Metadata for [u8; core::mem::size_of::<usize>()] {
     fn pack(self) -> Tag {
-        Tag(unsafe { core::mem::transmute(self) })
+        Tag(usize::from_ne_bytes(self))
+    }
+    fn unpack(val: Tag) -> Self {
+        val.0.to_ne_bytes()
+    }
+}
+
+/// `None` is stored as a tag of zero.
+#[cfg_attr(trybuild, diagnostic::do_not_recommend)]
+impl Metadata for Option<core::num::NonZeroUsize> {
+    fn pack(self) -> Tag {
+        Tag(self.map_or(0, core::num::NonZeroUsize::get))
     }
     fn unpack(val: Tag) -> Self {
-        unsafe { core::mem::transmute(val.0) }
+        core::num::NonZeroUsize::new(val.0)
     }
 }
 
-#[cfg(target_pointer_width = "64")]
+/// The first value is stored in the low half of the tag.
+#[cfg(tag_width_64)]
+#[cfg_attr(trybuild, diagnostic::do_not_recommend)]
+impl Metadata for (u32, u32) {
+    fn pack(self) -> Tag {
+        Tag(self.0 as usize | (self.1 as usize) << 32)
+    }
+    fn unpack(val: Tag) -> Self {
+        (val.0 as u32, (val.0 >> 32) as u32)
+    }
+}
+
+#[cfg(tag_width_64)]
+#[cfg_attr(trybuild, diagnostic::do_not_recommend)]
 impl Metadata for f64 {
     fn pack(self) -> Tag {
         Tag(self.to_bits() as usize)
@@ -175,7 +443,9 @@ impl Metadata for f64 {
     }
 }
 
-#[cfg(any(target_pointer_width = "32", target_pointer_width = "64"))]
+/// Not available on 16-bit targets, whose tags are too narrow for the bits of an `f32`.
+#[cfg(tag_width_32)]
+#[cfg_attr(trybuild, diagnostic::do_not_recommend)]
 impl Metadata for f32 {
     fn pack(self) -> Tag {
         Tag(self.to_bits() as usize)
@@ -185,37 +455,277 @@ impl Metadata for f32 {
     }
 }
 
-#[cfg(any(target_pointer_width = "32", target_pointer_width = "64"))]
+/// The duration is stored as a number of nanoseconds, saturating at `usize::MAX`.
+///
+/// That is enough for more than 500 years on 64-bit targets, but only about 4 seconds on 32-bit ones.
+/// `try_pack` returns `None` for durations that would saturate.
+/// ```
+/// # use fat_pointer_hack::{RefExt, FatRefExt, Metadata};
+/// use std::time::Duration;
+///
+/// let x = 5;
+/// for &d in &[Duration::ZERO, Duration::from_millis(250), Duration::from_nanos(usize::MAX as u64)] {
+///     assert_eq!((&x).tag(d).get_tag(), d);
+/// }
+///
+/// let too_long = Duration::from_nanos(usize::MAX as u64) + Duration::from_nanos(1);
+/// assert_eq!((&x).tag(too_long).get_tag(), Duration::from_nanos(usize::MAX as u64));
+/// assert!(too_long.try_pack().is_none());
+/// ```
+#[cfg_attr(trybuild, diagnostic::do_not_recommend)]
+impl Metadata for core::time::Duration {
+    fn pack(self) -> Tag {
+        self.try_pack().unwrap_or(Tag(usize::MAX))
+    }
+    fn unpack(val: Tag) -> Self {
+        Self::from_nanos(val.0 as u64)
+    }
+    fn try_pack(self) -> Option<Tag> {
+        use core::convert::TryFrom;
+        usize::try_from(self.as_nanos()).ok().map(Tag)
+    }
+}
+
+/// The address is stored as its `u32` value, the first octet being the most significant byte.
+///
+/// ```
+/// # use fat_pointer_hack::{RefExt, FatRefExt};
+/// use std::net::Ipv4Addr;
+///
+/// let buffer = [0u8; 16];
+/// for &addr in &[Ipv4Addr::UNSPECIFIED, Ipv4Addr::LOCALHOST, Ipv4Addr::BROADCAST] {
+///     assert_eq!((&buffer).tag(addr).get_tag(), addr);
+/// }
+/// assert_eq!((&buffer).tag(Ipv4Addr::LOCALHOST).cast_meta::<usize>().get_tag(), 0x7F00_0001);
+/// ```
+#[cfg(tag_width_32)]
+#[cfg_attr(trybuild, diagnostic::do_not_recommend)]
+impl Metadata for core::net::Ipv4Addr {
+    fn pack(self) -> Tag {
+        Tag(u32::from(self) as usize)
+    }
+    fn unpack(val: Tag) -> Self {
+        Self::from(val.0 as u32)
+    }
+}
+
+/// The bits of the half-precision float are stored, so NaN payloads survive.
+///
+/// ```
+/// # extern crate half;
+/// # extern crate fat_pointer_hack;
+/// # use fat_pointer_hack::{RefExt, FatRefExt};
+/// use half::f16;
+///
+/// # fn main() {
+/// let x = 5;
+/// let subnormal = f16::from_bits(0x0001);
+/// let nan = f16::from_bits(0x7E01);
+/// for &value in &[f16::ZERO, f16::ONE, subnormal, nan] {
+///     assert_eq!((&x).tag(value).get_tag().to_bits(), value.to_bits());
+/// }
+/// assert!(!subnormal.is_normal() && subnormal != f16::ZERO);
+/// assert!((&x).tag(nan).get_tag().is_nan());
+/// # }
+/// ```
+#[cfg(feature = "half")]
+#[cfg_attr(trybuild, diagnostic::do_not_recommend)]
+impl Metadata for half::f16 {
+    fn pack(self) -> Tag {
+        Tag(self.to_bits() as usize)
+    }
+    fn unpack(val: Tag) -> Self {
+        Self::from_bits(val.0 as u16)
+    }
+}
+
+/// The address of the pointer is stored, and its provenance is exposed so that
+/// the unpacked pointer can be used to access the same object again.
+///
+/// There's deliberately no impl for `&'static T`: bits that came from anywhere else
+/// (e.g. `set_raw_tag`) would unpack to a dangling reference in safe code.
+/// ```
+/// # use fat_pointer_hack::{RefExt, FatRefExt};
+/// struct Node {
+///     value: u32,
+/// }
+///
+/// let parent = Node { value: 7 };
+/// let buffer = [0u8; 16];
+/// let fat = (&buffer).tag(&parent as *const Node);
+///
+/// let back = fat.get_tag();
+/// assert_eq!(back, &parent as *const Node);
+/// assert_eq!(unsafe { (*back).value }, 7);
+/// ```
+#[cfg_attr(trybuild, diagnostic::do_not_recommend)]
+impl<T> Metadata for *const T {
+    fn pack(self) -> Tag {
+        Tag(self.expose_provenance())
+    }
+    fn unpack(val: Tag) -> Self {
+        core::ptr::with_exposed_provenance(val.0)
+    }
+}
+
+/// Like for `*const T`, the address is stored and its provenance exposed.
+///
+/// ```
+/// # use fat_pointer_hack::{RefExt, FatRefExt};
+/// let mut counter = 0u32;
+/// let buffer = [0u8; 16];
+/// let fat = (&buffer).tag(&mut counter as *mut u32);
+/// unsafe { *fat.get_tag() += 1 };
+/// assert_eq!(counter, 1);
+/// ```
+#[cfg_attr(trybuild, diagnostic::do_not_recommend)]
+impl<T> Metadata for *mut T {
+    fn pack(self) -> Tag {
+        Tag(self.expose_provenance())
+    }
+    fn unpack(val: Tag) -> Self {
+        core::ptr::with_exposed_provenance_mut(val.0)
+    }
+}
+
+/// Tags that are not a valid `char` unpack to `char::REPLACEMENT_CHARACTER`.
+///
+/// Those can only come from bits packed by another type:
+/// ```
+/// # use fat_pointer_hack::Metadata;
+/// assert_eq!(char::unpack(0xD800usize.pack()), '\u{FFFD}');
+/// assert_eq!(char::unpack(0xDFFFusize.pack()), '\u{FFFD}');
+/// assert_eq!(char::unpack(0x110000usize.pack()), '\u{FFFD}');
+/// assert_eq!(char::unpack(0x1_0000_0041usize.pack()), '\u{FFFD}');
+/// assert_eq!(char::unpack(0x41usize.pack()), 'A');
+/// ```
+#[cfg(tag_width_32)]
+#[cfg_attr(trybuild, diagnostic::do_not_recommend)]
 impl Metadata for char {
     fn pack(self) -> Tag {
-        Tag(unsafe{core::mem::transmute::<char, u32>(self)} as usize)
+        Tag(self as u32 as usize)
     }
     fn unpack(val: Tag) -> Self {
-        unsafe{core::mem::transmute(val.0 as u32)}
+        use core::convert::TryFrom;
+        u32::try_from(val.0)
+            .ok()
+            .and_then(char::from_u32)
+            .unwrap_or(char::REPLACEMENT_CHARACTER)
     }
 }
 
 /// An extension trait for methods on FatRef
 ///
 /// This needs to be an extension trait since there can't be any inherent methods on reference types.
-pub trait FatRefExt<'a> {
+pub trait FatRefExt<'a>: Sized {
     type Target;
     type Meta: Metadata;
-    fn from_ref(thin_ref: &Self::Target, metadata: Self::Meta) -> Self;
+    fn from_ref(thin_ref: &'a Self::Target, metadata: Self::Meta) -> Self;
     fn to_plain(self) -> &'a Self::Target;
     fn get_tag(self) -> Self::Meta;
     fn set_tag(&mut self, tag: Self::Meta);
+    fn into_parts(self) -> (&'a Self::Target, Self::Meta);
+    fn from_parts(parts: (&'a Self::Target, Self::Meta)) -> Self;
+    fn copied(self) -> (Self::Target, Self::Meta)
+    where
+        Self::Target: Copy;
+    fn cloned(self) -> (Self::Target, Self::Meta)
+    where
+        Self::Target: Clone;
+    fn checked_from_ref(thin_ref: &'a Self::Target, tag: Self::Meta) -> Option<Self>;
+    fn update_tag(&mut self, f: impl FnOnce(Self::Meta) -> Self::Meta);
+    fn map_tag(self, f: impl FnOnce(Self::Meta) -> Self::Meta) -> Self;
+    fn tag_ref(&self) -> Self::Meta;
+    fn replace_tag(&mut self, tag: Self::Meta) -> Self::Meta;
+    fn with_tag<R>(&mut self, temp: Self::Meta, f: impl FnOnce(&mut Self) -> R) -> R;
+    fn take_tag(&mut self) -> Self::Meta
+    where
+        Self::Meta: Default;
+    fn project<Inner, F: Fn(&Self::Target) -> &Inner>(self, f: F) -> FatRef<'a, Inner, Self::Meta>;
+    fn cast_meta<N: 'a + Metadata>(self) -> FatRef<'a, Self::Target, N>;
+    fn tag_bytes(self) -> [u8; core::mem::size_of::<usize>()];
+    fn set_tag_bytes(&mut self, bytes: [u8; core::mem::size_of::<usize>()]);
+    fn retag<N: 'a + Metadata>(self, f: impl FnOnce(Self::Meta) -> N) -> FatRef<'a, Self::Target, N>;
+    fn map<U>(self, f: impl FnOnce(&Self::Target) -> &U) -> FatRef<'a, U, Self::Meta>;
+    fn try_map<U>(
+        self,
+        f: impl FnOnce(&Self::Target) -> Option<&U>,
+    ) -> Result<FatRef<'a, U, Self::Meta>, Self>;
+    fn as_ptr(&self) -> *const Self::Target;
+    fn addr(&self) -> usize;
+    fn ptr_eq<N: Metadata>(a: &Self, b: &FatRef<'_, Self::Target, N>) -> bool;
+    fn same(a: &Self, b: &Self) -> bool;
+    fn to_raw_parts(self) -> (*const Self::Target, usize);
+    /// # Safety
+    ///
+    /// `ptr` must be valid for `&'a *ptr`, see the implementation for FatRef.
+    unsafe fn from_raw_parts(ptr: *const Self::Target, raw_tag: usize) -> Self;
+    /// # Safety
+    ///
+    /// `Self::Target` and `Q` must have the same layout, see the implementation for FatRef.
+    unsafe fn cast_pointee<Q>(self) -> FatRef<'a, Q, Self::Meta>;
+    fn eq_tagged<O: TaggedCmp<Target = Self::Target>>(self, other: O) -> bool
+    where
+        Self::Target: PartialEq,
+        Self::Meta: PartialEq<O::Meta>;
+    fn tag_eq<O: TaggedCmp>(self, other: O) -> bool
+    where
+        Self::Meta: Into<O::Meta>,
+        O::Meta: PartialEq;
+    fn value_eq<O: TaggedCmp>(self, other: O) -> bool
+    where
+        Self::Target: PartialEq<O::Target>;
+    fn copy_ref(&self) -> FatRef<'a, Self::Target, Self::Meta>;
+    fn saturating_set_tag(&mut self, tag: Self::Meta)
+    where
+        Self::Meta: core::convert::TryInto<usize> + From<usize>;
+    fn meta(self) -> Self::Meta;
+    fn set_meta(&mut self, tag: Self::Meta);
+    fn inspect_tag(self, f: impl FnOnce(&Self::Meta)) -> Self;
+    fn inspect(self, f: impl FnOnce(&Self::Target, &Self::Meta)) -> Self;
+    fn try_set_tag(&mut self, tag: Self::Meta) -> Result<(), TagOverflowError>;
+    fn try_replace_tag(&mut self, tag: Self::Meta) -> Result<Self::Meta, TagOverflowError>;
+    fn raw_tag(&self) -> usize;
+    fn set_raw_tag(&mut self, raw: usize);
+    fn copy_tag_from<Q>(&mut self, other: &FatPointee<Q, Self::Meta>);
+    #[cfg(feature = "bytemuck")]
+    fn wrap_pointee<W: bytemuck::TransparentWrapper<Self::Target>>(self) -> FatRef<'a, W, Self::Meta>;
+    #[cfg(feature = "bytemuck")]
+    fn peel_pointee<Inner>(self) -> FatRef<'a, Inner, Self::Meta>
+    where
+        Self::Target: bytemuck::TransparentWrapper<Inner>;
+    fn verify_tag<H: Hasher + Default>(&self) -> bool
+    where
+        Self::Target: Hash,
+        Self::Meta: PartialEq<usize>;
+    fn cmp_value_primary<O: TaggedCmp<Target = Self::Target, Meta = Self::Meta>>(self, other: O) -> Ordering
+    where
+        Self::Target: Ord,
+        Self::Meta: Ord;
+    fn cmp_tag_primary<O: TaggedCmp<Target = Self::Target, Meta = Self::Meta>>(self, other: O) -> Ordering
+    where
+        Self::Target: Ord,
+        Self::Meta: Ord;
+    fn scoped_tag(&mut self, tmp: Self::Meta) -> TagGuard<'_, 'a, Self::Target, Self::Meta>;
+    #[cfg(target_pointer_width = "64")]
+    fn to_bits(self) -> u128;
+    /// # Safety
+    ///
+    /// `bits` must come from `to_bits` on a FatRef that is valid for `'a`,
+    /// see the implementation for FatRef.
+    #[cfg(target_pointer_width = "64")]
+    unsafe fn from_bits(bits: u128) -> Self;
+    #[allow(clippy::wrong_self_convention)]
+    fn as_non_null(self) -> NonNull<Self::Target>;
+    fn tag_matches(self, pred: impl FnOnce(Self::Meta) -> bool) -> bool;
 }
 
 impl<'a, P, M: 'a + Metadata> FatRefExt<'a> for FatRef<'a, P, M> {
     type Target = P;
     type Meta = M;
     /// Makes a FatRef from a given reference and a tag.
-    fn from_ref(thin_ref: &P, tag: M) -> Self {
-        unsafe {
-            &*(core::slice::from_raw_parts(thin_ref as *const P as *const (), tag.pack().0)
-                as *const [()] as *const FatPointee<P, M>)
-        }
+    fn from_ref(thin_ref: &'a P, tag: M) -> Self {
+        unsafe { &*fat_ptr(thin_ref, tag.pack().0) }
     }
 
     /// Turns this FatRef back into a regular reference.
@@ -229,41 +739,2112 @@ impl<'a, P, M: 'a + Metadata> FatRefExt<'a> for FatRef<'a, P, M> {
     }
 
     /// Sets the tag of this FatRef to the given value.
+    ///
+    /// Only the tag half of the pointer is rewritten: the pointee is never moved or copied,
+    /// so the address stays the same. The same holds for all other methods that change the tag.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let x = [1u8; 64];
+    /// let mut fat = (&x).tag(0);
+    /// let addr = fat.as_ptr();
+    /// for tag in 1..10 {
+    ///     fat.set_tag(tag);
+    ///     fat.update_tag(|t| t * 2);
+    ///     assert_eq!(fat.as_ptr(), addr);
+    /// }
+    /// assert_eq!(addr, &x as *const [u8; 64]);
+    /// ```
     fn set_tag(&mut self, tag: M) {
         *self = Self::from_ref(self.to_plain(), tag);
     }
+
+    /// Splits this FatRef into a regular reference and its tag.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRef, FatRefExt};
+    /// // The regular reference lives as long as the FatRef did.
+    /// fn plain<'a>(fat: FatRef<'a, u32, f64>) -> &'a u32 {
+    ///     fat.into_parts().0
+    /// }
+    ///
+    /// let x = 5;
+    /// let fat = (&x).tag(std::f64::NAN);
+    /// assert_eq!(plain(fat), &5);
+    ///
+    /// let roundtrip = FatRef::from_parts(fat.into_parts());
+    /// assert_eq!(roundtrip.get_tag().to_bits(), fat.get_tag().to_bits());
+    /// assert!(std::ptr::eq(roundtrip.to_plain(), fat.to_plain()));
+    /// ```
+    fn into_parts(self) -> (&'a P, M) {
+        (self.to_plain(), self.get_tag())
+    }
+
+    /// Makes a FatRef from a regular reference and a tag, the inverse of `into_parts`.
+    fn from_parts((thin_ref, tag): (&'a P, M)) -> Self {
+        Self::from_ref(thin_ref, tag)
+    }
+
+    /// Returns a copy of the pointee together with the tag.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let x = 5;
+    /// let (value, tag) = (&x).tag('c').copied();
+    /// assert_eq!((value, tag), (5, 'c'));
+    /// ```
+    fn copied(self) -> (P, M)
+    where
+        P: Copy,
+    {
+        (self.pointee, self.get_tag())
+    }
+
+    /// Returns a clone of the pointee together with the tag.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let name = String::from("owned");
+    /// let (value, tag) = (&name).tag(2).cloned();
+    /// assert_eq!((value, tag), (String::from("owned"), 2));
+    /// ```
+    fn cloned(self) -> (P, M)
+    where
+        P: Clone,
+    {
+        (self.pointee.clone(), self.get_tag())
+    }
+
+    /// Makes a FatRef from a given reference and a tag, or returns `None` if the tag doesn't fit.
+    ///
+    /// `Duration` is one of the metadata types whose values can be too wide:
+    /// ```
+    /// # use fat_pointer_hack::{FatRef, FatRefExt};
+    /// use std::time::Duration;
+    /// let x = 5;
+    ///
+    /// let fat = FatRef::checked_from_ref(&x, Duration::from_secs(7)).unwrap();
+    /// assert_eq!(fat.get_tag(), Duration::from_secs(7));
+    ///
+    /// assert!(FatRef::checked_from_ref(&x, Duration::MAX).is_none());
+    /// ```
+    fn checked_from_ref(thin_ref: &'a P, tag: M) -> Option<Self> {
+        let tag = tag.try_pack()?;
+        Some(unsafe { &*fat_ptr(thin_ref, tag.0) })
+    }
+
+    /// Replaces the tag of this FatRef with the result of calling `f` on it.
+    ///
+    /// If `f` panics, the FatRef keeps its old tag.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt, Metadata, Tag};
+    /// // Not Copy, so the old tag has to be moved into the closure.
+    /// #[derive(Debug, PartialEq)]
+    /// struct Counter(usize);
+    ///
+    /// impl Metadata for Counter {
+    ///     fn pack(self) -> Tag {
+    ///         self.0.pack()
+    ///     }
+    ///     fn unpack(tag: Tag) -> Self {
+    ///         Counter(usize::unpack(tag))
+    ///     }
+    /// }
+    ///
+    /// let x = "hello";
+    /// let mut fat = (&x).tag(Counter(1));
+    /// fat.update_tag(|Counter(n)| Counter(n + 1));
+    /// assert_eq!(fat.get_tag(), Counter(2));
+    ///
+    /// let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    ///     fat.update_tag(|_| panic!("oops"));
+    /// }));
+    /// assert!(result.is_err());
+    /// assert_eq!(fat.get_tag(), Counter(2));
+    /// assert_eq!(*fat.to_plain(), "hello");
+    /// ```
+    fn update_tag(&mut self, f: impl FnOnce(M) -> M) {
+        let tag = f(self.get_tag());
+        self.set_tag(tag);
+    }
+
+    /// Returns this FatRef with its tag replaced by the result of calling `f` on it.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let x = 5;
+    /// let fat = (&x).tag(1).map_tag(|t| t * 10).map_tag(|t| t + 2);
+    /// assert_eq!(fat.get_tag(), 12);
+    /// ```
+    fn map_tag(mut self, f: impl FnOnce(M) -> M) -> Self {
+        self.update_tag(f);
+        self
+    }
+
+    /// Same as `get_tag`, but borrows this FatRef instead of consuming it.
+    ///
+    /// Useful in generic code where the FatRef isn't known to be `Copy`.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// fn tag_twice<'a, F: FatRefExt<'a, Meta = usize>>(fat: &F) -> usize {
+    ///     fat.tag_ref() + fat.tag_ref()
+    /// }
+    ///
+    /// let x = 5;
+    /// let fat = (&x).tag(21);
+    /// assert_eq!(tag_twice(&fat), 42);
+    /// ```
+    fn tag_ref(&self) -> M {
+        self.get_tag()
+    }
+
+    /// Sets the tag of this FatRef to the given value, returning the previous one.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt, Metadata, Tag};
+    /// #[derive(Debug, PartialEq, Clone, Copy)]
+    /// enum State {
+    ///     Idle,
+    ///     Running,
+    /// }
+    ///
+    /// impl Metadata for State {
+    ///     fn pack(self) -> Tag {
+    ///         (self as usize).pack()
+    ///     }
+    ///     fn unpack(tag: Tag) -> Self {
+    ///         if usize::unpack(tag) == 0 { State::Idle } else { State::Running }
+    ///     }
+    /// }
+    ///
+    /// let job = "job";
+    /// let mut fat = (&job).tag(State::Idle);
+    /// assert_eq!(fat.replace_tag(State::Running), State::Idle);
+    /// assert_eq!(fat.get_tag(), State::Running);
+    ///
+    /// assert_eq!(fat.replace_tag(State::Running), State::Running);
+    /// assert_eq!(fat.get_tag(), State::Running);
+    /// ```
+    fn replace_tag(&mut self, tag: M) -> M {
+        let old = self.get_tag();
+        self.set_tag(tag);
+        old
+    }
+
+    /// Sets the tag of this FatRef to `temp` while running `f`, then restores the previous tag.
+    ///
+    /// The previous tag is restored even if `f` panics, this is `scoped_tag` with a closure.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let x = 5;
+    /// let mut fat = (&x).tag('a');
+    ///
+    /// let seen = fat.with_tag('b', |fat| fat.get_tag());
+    /// assert_eq!(seen, 'b');
+    /// assert_eq!(fat.get_tag(), 'a');
+    ///
+    /// let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    ///     fat.with_tag('c', |_| panic!("oops"));
+    /// }));
+    /// assert!(result.is_err());
+    /// assert_eq!(fat.get_tag(), 'a');
+    /// ```
+    fn with_tag<R>(&mut self, temp: M, f: impl FnOnce(&mut Self) -> R) -> R {
+        f(&mut self.scoped_tag(temp))
+    }
+
+    /// Returns the tag of this FatRef and resets it to the default value.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let x = 5;
+    /// let mut counted = (&x).tag(3);
+    /// assert_eq!(counted.take_tag(), 3);
+    /// assert_eq!(counted.get_tag(), 0);
+    ///
+    /// let mut optional = (&x).tag(NonZeroUsize::new(3));
+    /// assert_eq!(optional.take_tag(), NonZeroUsize::new(3));
+    /// assert_eq!(optional.get_tag(), None);
+    /// assert_eq!(*optional.to_plain(), 5);
+    /// ```
+    fn take_tag(&mut self) -> M
+    where
+        M: Default,
+    {
+        self.replace_tag(M::default())
+    }
+
+    /// An alias for `map`.
+    ///
+    /// It only differs in taking an `Fn` instead of an `FnOnce`. New code should use `map`.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// #[repr(C)]
+    /// struct Point {
+    ///     x: u32,
+    ///     y: u32,
+    /// }
+    ///
+    /// let p = Point { x: 1, y: 2 };
+    /// let fat_y = (&p).tag('y').project(|p| &p.y);
+    ///
+    /// assert_eq!(fat_y.get_tag(), 'y');
+    /// assert_eq!(fat_y.to_plain(), &2);
+    /// assert!(std::ptr::eq(fat_y.to_plain(), &p.y));
+    /// ```
+    fn project<Inner, F: Fn(&P) -> &Inner>(self, f: F) -> FatRef<'a, Inner, M> {
+        self.map(f)
+    }
+
+    /// Reinterprets the raw tag bits of this FatRef as a different metadata type.
+    ///
+    /// Nothing is unpacked or packed, the pointer and the bits stay exactly the same.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let x = 5;
+    /// let bits = 1.5f64.to_bits();
+    ///
+    /// let as_float = (&x).tag(1.5f64);
+    /// let as_usize = as_float.cast_meta::<usize>();
+    /// assert_eq!(as_usize.get_tag() as u64, bits);
+    ///
+    /// let as_pair = as_usize.cast_meta::<(u32, u32)>();
+    /// assert_eq!(as_pair.get_tag(), (bits as u32, (bits >> 32) as u32));
+    ///
+    /// let back = as_pair.cast_meta::<f64>();
+    /// assert_eq!(back.get_tag(), 1.5);
+    /// assert!(std::ptr::eq(back.to_plain(), &x));
+    /// ```
+    ///
+    /// No value is decoded and encoded again on the way, so this is also
+    /// the way to view a float tag as its bits:
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let x = 5;
+    /// let bits = (&x).tag(0x3FC0_0000usize);
+    /// assert_eq!(bits.cast_meta::<f32>().get_tag(), 1.5);
+    /// assert_eq!(bits.cast_meta::<f32>().cast_meta::<usize>().get_tag(), 0x3FC0_0000);
+    /// ```
+    ///
+    /// The bits aren't checked against `N`, since every `Metadata::unpack` has to accept
+    /// any bits anyway, see the `Metadata` docs.
+    fn cast_meta<N: 'a + Metadata>(self) -> FatRef<'a, P, N> {
+        unsafe { &*fat_ptr(&self.pointee, self.unsize.len()) }
+    }
+
+    /// Returns the raw bits of the tag of this FatRef as bytes, in native byte order.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let x = 5;
+    /// let fat = (&x).tag(0x0102usize);
+    /// assert_eq!(fat.tag_bytes(), 0x0102usize.to_ne_bytes());
+    /// ```
+    fn tag_bytes(self) -> [u8; core::mem::size_of::<usize>()] {
+        self.unsize.len().to_ne_bytes()
+    }
+
+    /// Sets the raw bits of the tag of this FatRef from bytes, in native byte order.
+    ///
+    /// This works for every metadata type and bypasses `Metadata::pack`, see the `Metadata` docs.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let x = 5;
+    /// let mut fat = (&x).tag(0);
+    ///
+    /// let mut magic = [0; std::mem::size_of::<usize>()];
+    /// magic[..4].copy_from_slice(b"RUST");
+    /// fat.set_tag_bytes(magic);
+    ///
+    /// assert_eq!(&fat.tag_bytes()[..4], b"RUST");
+    /// assert_eq!(fat.get_tag(), usize::from_ne_bytes(magic));
+    /// ```
+    fn set_tag_bytes(&mut self, bytes: [u8; core::mem::size_of::<usize>()]) {
+        *self = unsafe { &*fat_ptr(&self.pointee, usize::from_ne_bytes(bytes)) };
+    }
+
+    /// Converts the tag of this FatRef to a different metadata type with `f`.
+    ///
+    /// Unlike `cast_meta`, this goes through the values, so the two types can use
+    /// completely different bit layouts.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt, Metadata, Tag};
+    /// #[derive(Debug, PartialEq, Clone, Copy)]
+    /// enum Color {
+    ///     Red = 1,
+    ///     Green = 2,
+    /// }
+    ///
+    /// // Stored as a letter, not as the discriminant.
+    /// impl Metadata for Color {
+    ///     fn pack(self) -> Tag {
+    ///         match self {
+    ///             Color::Red => 'r'.pack(),
+    ///             Color::Green => 'g'.pack(),
+    ///         }
+    ///     }
+    ///     fn unpack(tag: Tag) -> Self {
+    ///         if char::unpack(tag) == 'r' { Color::Red } else { Color::Green }
+    ///     }
+    /// }
+    ///
+    /// let x = 5;
+    /// let by_discriminant = (&x).tag(Color::Green).retag(|c| c as usize);
+    /// assert_eq!(by_discriminant.get_tag(), 2);
+    ///
+    /// let as_float = by_discriminant.retag(|n| n as f32);
+    /// assert_eq!(as_float.get_tag(), 2.0);
+    /// let as_int = as_float.retag(|f| f as usize + 1);
+    /// assert_eq!(as_int.get_tag(), 3);
+    /// assert!(std::ptr::eq(as_int.to_plain(), &x));
+    /// ```
+    fn retag<N: 'a + Metadata>(self, f: impl FnOnce(M) -> N) -> FatRef<'a, P, N> {
+        FatRef::from_ref(self.to_plain(), f(self.get_tag()))
+    }
+
+    /// Turns this FatRef into a FatRef to a part of the pointee, keeping the tag.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// struct User {
+    ///     name: String,
+    ///     marker: (),
+    /// }
+    ///
+    /// let user = User { name: "ferris".into(), marker: () };
+    /// let fat = (&user).tag(7);
+    ///
+    /// let name = fat.map(|u| &u.name);
+    /// assert_eq!((name.to_plain().as_str(), name.get_tag()), ("ferris", 7));
+    ///
+    /// let marker = fat.map(|u| &u.marker);
+    /// assert_eq!(marker.get_tag(), 7);
+    /// assert!(std::ptr::eq(marker.to_plain(), &user.marker));
+    /// ```
+    ///
+    /// The projected FatRef still borrows from the original owner:
+    /// ```compile_fail
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let name = {
+    ///     let user = (String::from("ferris"), 0);
+    ///     (&user).tag(7).map(|u| &u.0)
+    /// };
+    /// name.get_tag();
+    /// ```
+    fn map<U>(self, f: impl FnOnce(&P) -> &U) -> FatRef<'a, U, M> {
+        unsafe { &*fat_ptr(f(self.to_plain()), self.unsize.len()) }
+    }
+
+    /// Like `map`, but for projections that can fail.
+    ///
+    /// On failure, the original FatRef is given back.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let some = Some(5);
+    /// let inner = (&some).tag('s').try_map(|o| o.as_ref()).unwrap();
+    /// assert_eq!((inner.to_plain(), inner.get_tag()), (&5, 's'));
+    ///
+    /// let none: Option<u32> = None;
+    /// let original = (&none).tag('n').try_map(|o| o.as_ref()).unwrap_err();
+    /// assert_eq!((original.to_plain(), original.get_tag()), (&None, 'n'));
+    /// ```
+    fn try_map<U>(self, f: impl FnOnce(&P) -> Option<&U>) -> Result<FatRef<'a, U, M>, Self> {
+        match f(self.to_plain()) {
+            Some(projected) => Ok(unsafe { &*fat_ptr(projected, self.unsize.len()) }),
+            None => Err(self),
+        }
+    }
+
+    /// Returns a raw pointer to the pointee, read straight from the fat pointer.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let x = 5;
+    /// let fat = (&x).tag('p');
+    /// assert_eq!(fat.as_ptr(), fat.to_plain() as *const i32);
+    /// assert_eq!(fat.addr(), &x as *const i32 as usize);
+    /// ```
+    fn as_ptr(&self) -> *const P {
+        *self as *const FatPointee<P, M> as *const P
+    }
+
+    /// Returns the address of the pointee.
+    fn addr(&self) -> usize {
+        self.as_ptr() as usize
+    }
+
+    /// Checks whether two FatRefs point at the same address, ignoring their tags.
+    ///
+    /// The tags may even have different types. As with `core::ptr::eq`, distinct
+    /// zero-sized values can share an address.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let (x, y) = (5, 5);
+    /// assert!(FatRefExt::ptr_eq(&(&x).tag(1), &(&x).tag('b')));
+    /// assert!(!FatRefExt::ptr_eq(&(&x).tag(1), &(&y).tag(1)));
+    ///
+    /// let units = [(), ()];
+    /// assert!(FatRefExt::ptr_eq(&(&units[0]).tag(0), &(&units[1]).tag(1)));
+    /// ```
+    fn ptr_eq<N: Metadata>(a: &Self, b: &FatRef<'_, P, N>) -> bool {
+        a.as_ptr() == b.as_ptr()
+    }
+
+    /// Checks whether two FatRefs point at the same address and carry the same raw tag.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let (x, y) = (5, 5);
+    /// assert!(FatRefExt::same(&(&x).tag(1), &(&x).tag(1)));
+    /// assert!(!FatRefExt::same(&(&x).tag(1), &(&x).tag(2)));
+    /// assert!(!FatRefExt::same(&(&x).tag(1), &(&y).tag(1)));
+    /// ```
+    fn same(a: &Self, b: &Self) -> bool {
+        Self::ptr_eq(a, b) && a.unsize.len() == b.unsize.len()
+    }
+
+    /// Splits this FatRef into a raw pointer to the pointee and the packed tag.
+    ///
+    /// This is meant for passing a FatRef through code that only deals in raw pointers and
+    /// integers, like a C callback. Use `from_raw_parts` to put it back together.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRef, FatRefExt};
+    /// use std::os::raw::c_void;
+    ///
+    /// extern "C" fn callback(data: *const c_void, raw_tag: usize) -> u32 {
+    ///     let fat: FatRef<u32, char> = unsafe { FatRef::from_raw_parts(data as *const u32, raw_tag) };
+    ///     *fat.to_plain() + fat.get_tag() as u32
+    /// }
+    ///
+    /// let call: extern "C" fn(*const c_void, usize) -> u32 = callback;
+    /// let x = 1;
+    /// let (ptr, raw_tag) = (&x).tag('a').to_raw_parts();
+    /// assert_eq!(ptr, &x as *const u32);
+    /// assert_eq!(call(ptr as *const c_void, raw_tag), 98);
+    /// ```
+    fn to_raw_parts(self) -> (*const P, usize) {
+        (self.as_ptr(), self.unsize.len())
+    }
+
+    /// Makes a FatRef from a raw pointer to the pointee and a packed tag.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be non-null, properly aligned and point to a valid `P`
+    /// that isn't mutated for the whole (caller-chosen) lifetime `'a`, just like for `&*ptr`.
+    ///
+    /// There are no requirements on `raw_tag`: every `usize` is a valid length word,
+    /// and `Metadata::unpack` has to cope with arbitrary bits anyway.
+    /// Of course, only a `raw_tag` that came from `to_raw_parts` round-trips to the same tag.
+    unsafe fn from_raw_parts(ptr: *const P, raw_tag: usize) -> Self {
+        &*fat_ptr(ptr, raw_tag)
+    }
+
+    /// Reinterprets the pointee as a `Q`, keeping the address and the tag.
+    ///
+    /// This is the tagged version of `&*(p as *const P as *const Q)`.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRef, FatRefExt};
+    /// #[repr(transparent)]
+    /// struct Meters(f64);
+    ///
+    /// let distance = Meters(1.5);
+    /// let fat = (&distance).tag(3);
+    /// let raw: FatRef<f64, usize> = unsafe { fat.cast_pointee() };
+    /// assert_eq!((*raw.to_plain(), raw.get_tag()), (1.5, 3));
+    ///
+    /// let back: FatRef<Meters, usize> = unsafe { raw.cast_pointee() };
+    /// assert_eq!(back.to_plain().0, 1.5);
+    /// ```
+    ///
+    /// Casting is unsafe, since nothing checks that the layouts match:
+    /// ```compile_fail
+    /// # use fat_pointer_hack::{RefExt, FatRef, FatRefExt};
+    /// let x = 1u8;
+    /// let wide: FatRef<u64, usize> = (&x).tag(0).cast_pointee();
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// `P` and `Q` must have an identical layout (size, alignment and valid bit patterns),
+    /// for example because one is a `#[repr(transparent)]` wrapper around the other.
+    unsafe fn cast_pointee<Q>(self) -> FatRef<'a, Q, M> {
+        &*fat_ptr(self.as_ptr() as *const Q, self.unsize.len())
+    }
+
+    /// Checks whether the pointee and the tag are equal to those of `other`.
+    ///
+    /// `other` can be another fat reference or a `(&P, &M)` pair, see `TaggedCmp`.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let (x, y) = (5, 5);
+    /// let mut z = 5;
+    /// let fat = (&x).tag('a');
+    /// assert!(fat.eq_tagged((&y).tag('a')));
+    /// assert!(!fat.eq_tagged((&y).tag('b')));
+    /// assert!(!fat.eq_tagged((&6, &'a')));
+    /// assert!(fat.eq_tagged(&(&mut z).tag('a')));
+    /// ```
+    fn eq_tagged<O: TaggedCmp<Target = P>>(self, other: O) -> bool
+    where
+        P: PartialEq,
+        M: PartialEq<O::Meta>,
+    {
+        let (value, tag) = other.tagged_parts();
+        self.pointee == *value && self.get_tag() == tag
+    }
+
+    /// Checks whether the tag is equal to that of `other`, after converting it into the other's type.
+    ///
+    /// The pointees don't have to be of the same type.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// # #[cfg(target_pointer_width = "64")] {
+    /// let (x, y) = (5, 6);
+    /// let single = (&x).tag(1.5f32);
+    /// assert!(single.tag_eq((&x).tag(1.5f64)));
+    /// assert!(single.tag_eq((&y).tag(1.5f64)));
+    /// assert!(single.tag_eq((&"other type").tag(1.5f64)));
+    /// assert!(single.tag_eq((&"other type", &1.5f64)));
+    /// assert!(!single.tag_eq((&x).tag(2.5f64)));
+    /// # }
+    /// ```
+    fn tag_eq<O: TaggedCmp>(self, other: O) -> bool
+    where
+        M: Into<O::Meta>,
+        O::Meta: PartialEq,
+    {
+        self.get_tag().into() == other.tagged_parts().1
+    }
+
+    /// Checks whether the pointee is equal to that of `other`, ignoring the tags.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let (x, y) = (5, 6);
+    /// let fat = (&x).tag('a');
+    /// assert!(fat.value_eq((&x).tag(1)));
+    /// assert!(fat.value_eq((&5, &'b')));
+    /// assert!(!fat.value_eq((&y).tag('a')));
+    /// ```
+    fn value_eq<O: TaggedCmp>(self, other: O) -> bool
+    where
+        P: PartialEq<O::Target>,
+    {
+        self.pointee == *other.tagged_parts().0
+    }
+
+    /// Returns a copy of this FatRef, with the same tag and pointee.
+    ///
+    /// FatRefs are `Copy` anyway, this is for generic code that can only see `FatRefExt`.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// fn duplicate<'a, F: FatRefExt<'a> + 'a>(fat: &F) -> (F::Meta, *const F::Target) {
+    ///     let copy = fat.copy_ref();
+    ///     (copy.get_tag(), copy.as_ptr())
+    /// }
+    ///
+    /// let x = 5;
+    /// let fat = (&x).tag('c');
+    /// assert_eq!(duplicate(&fat), ('c', &x as *const i32));
+    /// assert!(FatRefExt::same(&fat.copy_ref(), &fat));
+    /// ```
+    fn copy_ref(&self) -> FatRef<'a, P, M> {
+        self
+    }
+
+    /// Sets the tag to `tag`, clamped to the largest value a `usize` can hold.
+    ///
+    /// This is for integer-like metadata, which converts to and from a `usize`.
+    /// Values that don't fit into a `usize` are stored as `usize::MAX` instead of being truncated:
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt, Metadata, Tag};
+    /// use std::convert::TryFrom;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Count(u128);
+    ///
+    /// impl TryFrom<Count> for usize {
+    ///     type Error = std::num::TryFromIntError;
+    ///     fn try_from(count: Count) -> Result<usize, Self::Error> {
+    ///         usize::try_from(count.0)
+    ///     }
+    /// }
+    ///
+    /// impl From<usize> for Count {
+    ///     fn from(n: usize) -> Count {
+    ///         Count(n as u128)
+    ///     }
+    /// }
+    /// # impl Metadata for Count {
+    /// #     fn pack(self) -> Tag { (self.0 as usize).pack() }
+    /// #     fn unpack(tag: Tag) -> Self { Count(usize::unpack(tag) as u128) }
+    /// # }
+    ///
+    /// let x = 5;
+    /// let mut fat = (&x).tag(Count(0));
+    /// fat.saturating_set_tag(Count(7));
+    /// assert_eq!(fat.get_tag(), Count(7));
+    /// fat.saturating_set_tag(Count(u128::MAX));
+    /// assert_eq!(fat.get_tag(), Count(usize::MAX as u128));
+    ///
+    /// let mut plain = (&x).tag(0);
+    /// plain.saturating_set_tag(usize::MAX);
+    /// assert_eq!(plain.get_tag(), usize::MAX);
+    /// ```
+    fn saturating_set_tag(&mut self, tag: M)
+    where
+        M: core::convert::TryInto<usize> + From<usize>,
+    {
+        let clamped = tag.try_into().unwrap_or(usize::MAX);
+        self.set_tag(M::from(clamped));
+    }
+
+    /// An alias for `get_tag`.
+    ///
+    /// A FatRef derefs to its pointee, but method calls look at the FatRef itself first:
+    /// trait methods on `&FatPointee` are found before autoderef ever reaches the pointee.
+    /// So `fat.meta()` is always this method, and the pointee's own `meta` (or `tag`)
+    /// has to be reached by dereferencing explicitly:
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// struct Node;
+    /// impl Node {
+    ///     fn tag(&self) -> &'static str { "node tag" }
+    ///     fn meta(&self) -> &'static str { "node meta" }
+    /// }
+    ///
+    /// let node = Node;
+    /// let mut fat = (&node).with_meta(1);
+    /// fat.set_meta(2);
+    /// assert_eq!(fat.meta(), 2);
+    /// assert_eq!((**fat).meta(), "node meta");
+    /// assert_eq!((fat.to_plain().tag(), fat.to_plain().meta()), ("node tag", "node meta"));
+    /// ```
+    fn meta(self) -> M {
+        self.get_tag()
+    }
+
+    /// An alias for `set_tag`.
+    fn set_meta(&mut self, tag: M) {
+        self.set_tag(tag)
+    }
+
+    /// Calls `f` with the tag and returns this FatRef unchanged.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let pair = (1, 'x');
+    /// let mut seen = Vec::new();
+    /// let second = (&pair)
+    ///     .tag(1)
+    ///     .inspect_tag(|t| seen.push(*t))
+    ///     .map_tag(|t| t + 1)
+    ///     .inspect(|p, t| assert_eq!((p.0, *t), (1, 2)))
+    ///     .map(|p| &p.1)
+    ///     .inspect_tag(|t| seen.push(*t));
+    /// assert_eq!(*second.to_plain(), 'x');
+    /// assert_eq!(seen, [1, 2]);
+    /// ```
+    fn inspect_tag(self, f: impl FnOnce(&M)) -> Self {
+        f(&self.get_tag());
+        self
+    }
+
+    /// Calls `f` with the pointee and the tag and returns this FatRef unchanged.
+    fn inspect(self, f: impl FnOnce(&P, &M)) -> Self {
+        f(&self.pointee, &self.get_tag());
+        self
+    }
+
+    /// Sets the tag of this FatRef, or returns an error if it doesn't fit.
+    ///
+    /// On error, the previous tag is left untouched.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// use std::time::Duration;
+    /// let x = 5;
+    /// let mut fat = (&x).tag(Duration::from_secs(1));
+    /// assert_eq!(fat.try_set_tag(Duration::from_secs(3)), Ok(()));
+    /// assert!(fat.try_set_tag(Duration::MAX).is_err());
+    /// assert_eq!(fat.get_tag(), Duration::from_secs(3));
+    ///
+    /// assert_eq!(fat.try_replace_tag(Duration::from_secs(4)), Ok(Duration::from_secs(3)));
+    /// assert!(fat.try_replace_tag(Duration::MAX).is_err());
+    /// assert_eq!((fat.get_tag(), *fat.to_plain()), (Duration::from_secs(4), 5));
+    /// ```
+    fn try_set_tag(&mut self, tag: M) -> Result<(), TagOverflowError> {
+        let raw = try_pack_raw(tag)?;
+        *self = unsafe { &*fat_ptr(self.as_ptr(), raw) };
+        Ok(())
+    }
+
+    /// Replaces the tag of this FatRef and returns the old one, or returns an error if the new one doesn't fit.
+    ///
+    /// On error, the previous tag is left untouched.
+    fn try_replace_tag(&mut self, tag: M) -> Result<M, TagOverflowError> {
+        let old = self.get_tag();
+        self.try_set_tag(tag)?;
+        Ok(old)
+    }
+
+    /// Returns the raw bits of the tag, regardless of the metadata type.
+    ///
+    /// This is what `Metadata::pack` produced for the current tag:
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt, Metadata, SmallStr, TypeTag, Generation};
+    /// use std::net::Ipv4Addr;
+    /// use std::num::NonZeroUsize;
+    /// use std::time::Duration;
+    ///
+    /// fn agrees<M: Metadata + Clone>(tag: M) -> bool {
+    ///     let x = 5;
+    ///     (&x).tag(tag.clone()).raw_tag() == tag.pack().into_raw()
+    /// }
+    ///
+    /// assert!(agrees(42usize));
+    /// assert!(agrees([7u8; std::mem::size_of::<usize>()]));
+    /// assert!(agrees(NonZeroUsize::new(3)));
+    /// assert!(agrees((1u32, 2u32)));
+    /// assert!(agrees(-1.5f64));
+    /// assert!(agrees(2.5f32));
+    /// assert!(agrees('z'));
+    /// assert!(agrees(Duration::from_micros(3)));
+    /// assert!(agrees(Ipv4Addr::LOCALHOST));
+    /// assert!(agrees(TypeTag(9)));
+    /// assert!(agrees(SmallStr::new("raw").unwrap()));
+    /// assert!(agrees(Generation::new(1, 2)));
+    /// ```
+    fn raw_tag(&self) -> usize {
+        self.unsize.len()
+    }
+
+    /// Sets the raw bits of the tag, regardless of the metadata type.
+    ///
+    /// This bypasses `Metadata::pack`, so `get_tag` will give whatever `Metadata::unpack`
+    /// makes of these bits, see the `Metadata` docs.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let x = 5;
+    /// let mut fat = (&x).tag('a');
+    /// fat.set_raw_tag('b' as usize);
+    /// assert_eq!((fat.get_tag(), fat.raw_tag()), ('b', 0x62));
+    /// ```
+    fn set_raw_tag(&mut self, raw: usize) {
+        *self = unsafe { &*fat_ptr(self.as_ptr(), raw) };
+    }
+
+    /// Copies the raw tag of `other`, which may point at a different type, into this FatRef.
+    ///
+    /// `other` can be a FatRef or a FatRefMut, it is left unchanged.
+    /// The bits are copied without being unpacked, see the `Metadata` docs.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let (old, new) = ("old", 5u64);
+    /// let old_fat = (&old).tag('o');
+    /// let mut new_fat = (&new).tag('n');
+    ///
+    /// new_fat.copy_tag_from(&old_fat);
+    /// assert_eq!((*new_fat.to_plain(), new_fat.get_tag()), (5, 'o'));
+    /// assert_eq!((*old_fat.to_plain(), old_fat.get_tag()), ("old", 'o'));
+    /// ```
+    fn copy_tag_from<Q>(&mut self, other: &FatPointee<Q, M>) {
+        self.set_raw_tag(other.unsize.len());
+    }
+
+    /// Views the pointee as a transparent wrapper around it, keeping the tag.
+    ///
+    /// This is a safe version of `cast_pointee` for types that implement `bytemuck::TransparentWrapper`.
+    /// ```
+    /// # extern crate bytemuck;
+    /// # extern crate fat_pointer_hack;
+    /// # use fat_pointer_hack::{RefExt, FatRef, FatRefExt};
+    /// use bytemuck::TransparentWrapper;
+    ///
+    /// #[repr(transparent)]
+    /// struct Meters(f64);
+    /// unsafe impl TransparentWrapper<f64> for Meters {}
+    ///
+    /// # fn main() {
+    /// let distance = 1.5;
+    /// let meters: FatRef<Meters, char> = (&distance).tag('m').wrap_pointee();
+    /// assert_eq!((meters.to_plain().0, meters.get_tag()), (1.5, 'm'));
+    ///
+    /// let raw: FatRef<f64, char> = meters.peel_pointee();
+    /// assert_eq!(*raw.to_plain(), 1.5);
+    /// # }
+    /// ```
+    #[cfg(feature = "bytemuck")]
+    fn wrap_pointee<W: bytemuck::TransparentWrapper<P>>(self) -> FatRef<'a, W, M> {
+        // TransparentWrapper guarantees that W and P have the same layout.
+        unsafe { self.cast_pointee() }
+    }
+
+    /// Views the pointee, a transparent wrapper, as the type it wraps, keeping the tag.
+    #[cfg(feature = "bytemuck")]
+    fn peel_pointee<Inner>(self) -> FatRef<'a, Inner, M>
+    where
+        P: bytemuck::TransparentWrapper<Inner>,
+    {
+        // TransparentWrapper guarantees that P and Inner have the same layout.
+        unsafe { self.cast_pointee() }
+    }
+
+    /// Checks that the tag is the truncated hash of the pointee, as set by `HashTagExt::tag_hash`.
+    fn verify_tag<H: Hasher + Default>(&self) -> bool
+    where
+        P: Hash,
+        M: PartialEq<usize>,
+    {
+        self.get_tag() == hashtag::truncated_hash::<H, P>(&self.pointee)
+    }
+
+    /// Compares by pointee first, then by tag to break ties.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let (one, two) = (1, 2);
+    /// let mut refs = [(&two).tag(0), (&one).tag(9), (&one).tag(3)];
+    ///
+    /// refs.sort_by(|a, b| a.cmp_value_primary(*b));
+    /// let sorted: Vec<_> = refs.iter().map(|r| (*r.to_plain(), r.get_tag())).collect();
+    /// assert_eq!(sorted, [(1, 3), (1, 9), (2, 0)]);
+    ///
+    /// refs.sort_by(|a, b| a.cmp_tag_primary(*b));
+    /// let sorted: Vec<_> = refs.iter().map(|r| (*r.to_plain(), r.get_tag())).collect();
+    /// assert_eq!(sorted, [(2, 0), (1, 3), (1, 9)]);
+    /// ```
+    fn cmp_value_primary<O: TaggedCmp<Target = P, Meta = M>>(self, other: O) -> Ordering
+    where
+        P: Ord,
+        M: Ord,
+    {
+        let (pointee, tag) = other.tagged_parts();
+        self.pointee.cmp(pointee).then_with(|| self.get_tag().cmp(&tag))
+    }
+
+    /// Compares by tag first, then by pointee to break ties.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// use std::cmp::Ordering;
+    ///
+    /// let (one, two) = (1, 2);
+    /// assert_eq!((&one).tag(5).cmp_tag_primary((&two).tag(4)), Ordering::Greater);
+    /// assert_eq!((&one).tag(5).cmp_value_primary((&two).tag(4)), Ordering::Less);
+    /// assert_eq!((&one).tag(5).cmp_tag_primary((&two, &5)), Ordering::Less);
+    /// ```
+    fn cmp_tag_primary<O: TaggedCmp<Target = P, Meta = M>>(self, other: O) -> Ordering
+    where
+        P: Ord,
+        M: Ord,
+    {
+        let (pointee, tag) = other.tagged_parts();
+        self.get_tag().cmp(&tag).then_with(|| self.pointee.cmp(pointee))
+    }
+
+    /// Sets the tag until the returned guard is dropped, then restores the previous one.
+    ///
+    /// See `TagGuard` for examples.
+    fn scoped_tag(&mut self, tmp: M) -> TagGuard<'_, 'a, P, M> {
+        TagGuard::new(self, tmp)
+    }
+
+    /// Packs this FatRef into a `u128`, the address in the low 64 bits and the raw tag in the high 64 bits.
+    ///
+    /// The provenance of the address is exposed, so that `from_bits` can pick it up again.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRef, FatRefExt};
+    /// use std::sync::atomic::{AtomicU64, Ordering};
+    ///
+    /// let x = 42u64;
+    /// let bits = (&x).tag('q').to_bits();
+    /// assert_eq!(bits as u64 as usize, &x as *const u64 as usize);
+    /// assert_eq!((bits >> 64) as u32, 'q' as u32);
+    ///
+    /// // Stash the two halves somewhere that only holds integers.
+    /// let cell = [AtomicU64::new(bits as u64), AtomicU64::new((bits >> 64) as u64)];
+    /// let bits = cell[0].load(Ordering::Relaxed) as u128 | (cell[1].load(Ordering::Relaxed) as u128) << 64;
+    ///
+    /// let back: FatRef<u64, char> = unsafe { FatRef::from_bits(bits) };
+    /// assert_eq!((*back.to_plain(), back.get_tag()), (42, 'q'));
+    /// ```
+    #[cfg(target_pointer_width = "64")]
+    fn to_bits(self) -> u128 {
+        self.as_ptr().expose_provenance() as u128 | (self.raw_tag() as u128) << 64
+    }
+
+    /// Unpacks a FatRef from the `u128` made by `to_bits`.
+    ///
+    /// # Safety
+    ///
+    /// The address is turned back into a pointer with `core::ptr::with_exposed_provenance`.
+    /// That pointer is only usable if the provenance of the address was exposed before,
+    /// which `to_bits` does. An address that was obtained in some other way,
+    /// e.g. by a plain `as usize` cast followed by arithmetic, doesn't carry over the right to access the pointee.
+    ///
+    /// Beyond that, the low 64 bits must point to a valid `P` that isn't mutated
+    /// for the whole (caller-chosen) lifetime `'a`, just like for `from_raw_parts`.
+    #[cfg(target_pointer_width = "64")]
+    unsafe fn from_bits(bits: u128) -> Self {
+        let ptr = core::ptr::with_exposed_provenance::<P>(bits as u64 as usize);
+        Self::from_raw_parts(ptr, (bits >> 64) as usize)
+    }
+
+    /// Returns a non-null pointer to the pointee, dropping the tag.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let x = 5;
+    /// let ptr = (&x).tag('p').as_non_null();
+    /// assert_eq!(ptr.as_ptr() as *const i32, &x as *const i32);
+    /// assert_eq!(unsafe { *ptr.as_ref() }, 5);
+    /// ```
+    fn as_non_null(self) -> NonNull<P> {
+        NonNull::from(self.to_plain())
+    }
+
+    /// Checks whether the tag satisfies `pred`.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// let words = ["a", "bb", "ccc", "dddd"];
+    /// let long: Vec<&str> = words
+    ///     .iter()
+    ///     .map(|w| w.tag_by(|w| w.len()))
+    ///     .filter(|r| r.tag_matches(|t| t > 2))
+    ///     .map(|r| *r.to_plain())
+    ///     .collect();
+    /// assert_eq!(long, ["ccc", "dddd"]);
+    /// ```
+    fn tag_matches(self, pred: impl FnOnce(M) -> bool) -> bool {
+        pred(self.get_tag())
+    }
 }
 
 /// An extension trait for methods on FatRefMut
 ///
 /// This needs to be an extension trait since there can't be any inherent methods on reference types.
-pub trait FatRefMutExt<'a> {
+pub trait FatRefMutExt<'a>: Sized {
     type Target;
     type Meta : Metadata;
-    fn from_ref_mut(thin_ref: &mut Self::Target, tag: Self::Meta) -> Self;
+    fn from_ref_mut(thin_ref: &'a mut Self::Target, tag: Self::Meta) -> Self;
+    #[allow(clippy::wrong_self_convention)]
     fn to_plain_mut(self) -> &'a mut Self::Target;
+    fn get_tag(&self) -> Self::Meta;
+    fn set_tag(&mut self, tag: Self::Meta);
+    fn downgrade(self) -> FatRef<'a, Self::Target, Self::Meta>;
+    fn reborrow(&mut self) -> FatRefMut<'_, Self::Target, Self::Meta>;
+    fn into_parts_mut(self) -> (&'a mut Self::Target, Self::Meta);
+    fn from_parts_mut(parts: (&'a mut Self::Target, Self::Meta)) -> Self;
+    fn copied(&self) -> (Self::Target, Self::Meta)
+    where
+        Self::Target: Copy;
+    fn cloned(&self) -> (Self::Target, Self::Meta)
+    where
+        Self::Target: Clone;
+    fn update_tag(&mut self, f: impl FnOnce(Self::Meta) -> Self::Meta);
+    fn map_tag(self, f: impl FnOnce(Self::Meta) -> Self::Meta) -> Self;
+    fn replace_tag(&mut self, tag: Self::Meta) -> Self::Meta;
+    fn take_tag(&mut self) -> Self::Meta
+    where
+        Self::Meta: Default;
+    fn cast_meta<N: 'a + Metadata>(self) -> FatRefMut<'a, Self::Target, N>;
+    fn retag<N: 'a + Metadata>(self, f: impl FnOnce(Self::Meta) -> N) -> FatRefMut<'a, Self::Target, N>;
+    fn map_mut<U>(self, f: impl FnOnce(&mut Self::Target) -> &mut U) -> FatRefMut<'a, U, Self::Meta>;
+    fn try_map_mut<U>(
+        self,
+        f: impl FnOnce(&mut Self::Target) -> Option<&mut U>,
+    ) -> Result<FatRefMut<'a, U, Self::Meta>, Self>;
+    fn map_split<U, V>(
+        self,
+        f: impl FnOnce(&mut Self::Target) -> (&mut U, &mut V),
+    ) -> (FatRefMut<'a, U, Self::Meta>, FatRefMut<'a, V, Self::Meta>);
+    fn as_ptr(&self) -> *const Self::Target;
+    fn as_mut_ptr(&mut self) -> *mut Self::Target;
+    fn addr(&self) -> usize;
+    #[allow(clippy::wrong_self_convention)]
+    fn to_raw_parts_mut(self) -> (*mut Self::Target, usize);
+    /// # Safety
+    ///
+    /// `ptr` must be valid for `&'a mut *ptr`, see the implementation for FatRefMut.
+    unsafe fn from_raw_parts_mut(ptr: *mut Self::Target, raw_tag: usize) -> Self;
+    /// # Safety
+    ///
+    /// `Self::Target` and `Q` must have the same layout, see the implementation for FatRefMut.
+    unsafe fn cast_pointee<Q>(self) -> FatRefMut<'a, Q, Self::Meta>;
+    fn saturating_set_tag(&mut self, tag: Self::Meta)
+    where
+        Self::Meta: core::convert::TryInto<usize> + From<usize>;
+    fn meta(&self) -> Self::Meta;
+    fn set_meta(&mut self, tag: Self::Meta);
+    fn inspect_tag(self, f: impl FnOnce(&Self::Meta)) -> Self;
+    fn inspect(self, f: impl FnOnce(&Self::Target, &Self::Meta)) -> Self;
+    fn try_set_tag(&mut self, tag: Self::Meta) -> Result<(), TagOverflowError>;
+    fn try_replace_tag(&mut self, tag: Self::Meta) -> Result<Self::Meta, TagOverflowError>;
+    fn split_tag_mut(&mut self) -> (&mut Self::Target, TagProxy<'_, Self::Target, Self::Meta>);
+    fn raw_tag(&self) -> usize;
+    fn set_raw_tag(&mut self, raw: usize);
+    fn copy_tag_from<Q>(&mut self, other: &FatPointee<Q, Self::Meta>);
+    #[cfg(feature = "bytemuck")]
+    fn wrap_pointee<W: bytemuck::TransparentWrapper<Self::Target>>(self) -> FatRefMut<'a, W, Self::Meta>;
+    #[cfg(feature = "bytemuck")]
+    fn peel_pointee<Inner>(self) -> FatRefMut<'a, Inner, Self::Meta>
+    where
+        Self::Target: bytemuck::TransparentWrapper<Inner>;
+    fn verify_tag<H: Hasher + Default>(&self) -> bool
+    where
+        Self::Target: Hash,
+        Self::Meta: PartialEq<usize>;
+    /// # Safety
+    ///
+    /// Callers must only leave behind a word that `Self::Meta::pack` could have produced,
+    /// see the implementation for FatRefMut.
+    unsafe fn tag_word_mut(&mut self) -> &mut usize;
+    #[allow(clippy::wrong_self_convention)]
+    fn as_non_null(self) -> NonNull<Self::Target>;
+    fn tag_matches(&self, pred: impl FnOnce(Self::Meta) -> bool) -> bool;
 }
 
 impl<'a, P, M: 'a + Metadata> FatRefMutExt<'a> for FatRefMut<'a, P, M> {
     type Target = P;
     type Meta = M;
     /// Makes a FatRefMut from a given mutable reference and a tag.
-    fn from_ref_mut(thin_ref: &mut P, tag: M) -> Self {
-        unsafe {
-            &mut *(core::slice::from_raw_parts_mut(thin_ref as *mut P as *mut (), tag.pack().0)
-                as *mut [()] as *mut FatPointee<P, M>)
-        }
+    fn from_ref_mut(thin_ref: &'a mut P, tag: M) -> Self {
+        unsafe { &mut *fat_ptr_mut(thin_ref, tag.pack().0) }
     }
     /// Turns this FatRefMut back into a regular mutable reference.
     fn to_plain_mut(self) -> &'a mut P {
         &mut self.pointee
     }
+
+    /// Returns the tag of this FatRefMut
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut x = 5;
+    /// let fat_mut_ref = (&mut x).tag(42);
+    /// assert_eq!(fat_mut_ref.get_tag(), 42);
+    /// ```
+    fn get_tag(&self) -> M {
+        M::unpack(Tag(self.unsize.len()))
+    }
+
+    /// Sets the tag of this FatRefMut to the given value.
+    ///
+    /// The pointee is never moved, so its address stays the same:
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut x = [1u8; 64];
+    /// let addr = &mut x as *mut [u8; 64];
+    /// let mut fat = (&mut x).tag('a');
+    /// for tag in "tagged".chars() {
+    ///     fat.set_tag(tag);
+    ///     fat.update_tag(|t| t.to_ascii_uppercase());
+    ///     assert_eq!(fat.as_mut_ptr(), addr);
+    /// }
+    /// ```
+    ///
+    /// The reference keeps pointing at the same value and stays exclusive:
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut x = vec![1, 2, 3];
+    /// let mut fat_mut_ref = (&mut x).tag(0);
+    ///
+    /// fat_mut_ref.set_tag(7);
+    /// fat_mut_ref.as_mut().push(4);
+    ///
+    /// assert_eq!(fat_mut_ref.get_tag(), 7);
+    /// assert_eq!(x, [1, 2, 3, 4]);
+    /// ```
+    ///
+    /// ```compile_fail
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut x = vec![1, 2, 3];
+    /// let mut fat_mut_ref = (&mut x).tag(0);
+    ///
+    /// fat_mut_ref.set_tag(7);
+    /// x.push(4); // Doesn't compile - x is still mutably borrowed!
+    /// fat_mut_ref.as_mut().push(5);
+    /// ```
+    fn set_tag(&mut self, tag: M) {
+        let thin: *mut P = &mut self.pointee;
+        *self = unsafe { &mut *fat_ptr_mut(thin, tag.pack().0) };
+    }
+
+    /// Turns this FatRefMut into a shared FatRef to the same value, keeping the tag.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt, FatRefMutExt};
+    /// let mut x = 5;
+    /// let shared = (&mut x).tag(3).downgrade();
+    /// let other = shared;
+    ///
+    /// assert_eq!(shared.get_tag(), 3);
+    /// assert_eq!(other.to_plain(), &5);
+    /// ```
+    fn downgrade(self) -> FatRef<'a, P, M> {
+        self
+    }
+
+    /// Reborrows this FatRefMut for a shorter lifetime, keeping the tag.
+    ///
+    /// Just like with a plain `&mut T`, this lets you pass it to a function
+    /// without giving it away:
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMut, FatRefMutExt};
+    /// fn bump(fat: FatRefMut<u32, usize>) {
+    ///     *fat.to_plain_mut() += 1;
+    /// }
+    ///
+    /// let mut x = 0;
+    /// let mut fat = (&mut x).tag(9);
+    /// bump(fat.reborrow());
+    /// bump(fat.reborrow());
+    /// assert_eq!(fat.get_tag(), 9);
+    /// assert_eq!(x, 2);
+    /// ```
+    ///
+    /// The reborrow can not outlive the original:
+    /// ```compile_fail
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut x = 0;
+    /// let short;
+    /// {
+    ///     let mut fat = (&mut x).tag(9);
+    ///     short = fat.reborrow();
+    /// }
+    /// short.get_tag();
+    /// ```
+    fn reborrow(&mut self) -> FatRefMut<'_, P, M> {
+        self
+    }
+
+    /// Splits this FatRefMut into a regular mutable reference and its tag.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMut, FatRefMutExt};
+    /// let mut x = 5;
+    /// {
+    ///     let fat = (&mut x).tag(usize::MAX);
+    ///     let (plain, tag) = fat.into_parts_mut();
+    ///     *plain += 1;
+    ///     assert_eq!(tag, usize::MAX);
+    ///
+    ///     let roundtrip = FatRefMut::from_parts_mut((plain, tag));
+    ///     assert_eq!(roundtrip.get_tag(), usize::MAX);
+    /// }
+    /// assert_eq!(x, 6);
+    /// ```
+    fn into_parts_mut(self) -> (&'a mut P, M) {
+        let tag = self.get_tag();
+        (self.to_plain_mut(), tag)
+    }
+
+    /// Makes a FatRefMut from a regular mutable reference and a tag, the inverse of `into_parts_mut`.
+    fn from_parts_mut((thin_ref, tag): (&'a mut P, M)) -> Self {
+        Self::from_ref_mut(thin_ref, tag)
+    }
+
+    /// Returns a copy of the pointee together with the tag, leaving this FatRefMut usable.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut x = 5;
+    /// let mut fat = (&mut x).tag('c');
+    /// let (value, tag) = fat.copied();
+    /// fat.set_tag('d');
+    /// assert_eq!((value, tag, fat.get_tag()), (5, 'c', 'd'));
+    /// ```
+    fn copied(&self) -> (P, M)
+    where
+        P: Copy,
+    {
+        (self.pointee, self.get_tag())
+    }
+
+    /// Returns a clone of the pointee together with the tag, leaving this FatRefMut usable.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut v = vec![1, 2];
+    /// let fat = (&mut v).tag(1);
+    /// let (snapshot, tag) = fat.cloned();
+    /// fat.to_plain_mut().push(3);
+    /// assert_eq!((snapshot, tag), (vec![1, 2], 1));
+    /// assert_eq!(v, [1, 2, 3]);
+    /// ```
+    fn cloned(&self) -> (P, M)
+    where
+        P: Clone,
+    {
+        (self.pointee.clone(), self.get_tag())
+    }
+
+    /// Replaces the tag of this FatRefMut with the result of calling `f` on it.
+    ///
+    /// If `f` panics, the FatRefMut keeps its old tag.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut x = 0;
+    /// let mut fat = (&mut x).tag(1);
+    /// fat.update_tag(|t| t + 1);
+    /// assert_eq!(fat.get_tag(), 2);
+    ///
+    /// let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    ///     fat.update_tag(|_| panic!("oops"));
+    /// }));
+    /// assert!(result.is_err());
+    /// assert_eq!(fat.get_tag(), 2);
+    /// *fat.as_mut() = 7;
+    /// assert_eq!(x, 7);
+    /// ```
+    fn update_tag(&mut self, f: impl FnOnce(M) -> M) {
+        let tag = f(self.get_tag());
+        self.set_tag(tag);
+    }
+
+    /// Returns this FatRefMut with its tag replaced by the result of calling `f` on it.
+    fn map_tag(mut self, f: impl FnOnce(M) -> M) -> Self {
+        self.update_tag(f);
+        self
+    }
+
+    /// Sets the tag of this FatRefMut to the given value, returning the previous one.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut x = 0;
+    /// let mut fat = (&mut x).tag('a');
+    /// assert_eq!(fat.replace_tag('b'), 'a');
+    /// assert_eq!(fat.replace_tag('b'), 'b');
+    /// assert_eq!(fat.get_tag(), 'b');
+    /// ```
+    fn replace_tag(&mut self, tag: M) -> M {
+        let old = self.get_tag();
+        self.set_tag(tag);
+        old
+    }
+
+    /// Returns the tag of this FatRefMut and resets it to the default value.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let mut x = 5;
+    /// let mut counted = (&mut x).tag(3);
+    /// assert_eq!(counted.take_tag(), 3);
+    /// assert_eq!(counted.get_tag(), 0);
+    ///
+    /// let mut optional = (&mut x).tag(NonZeroUsize::new(3));
+    /// assert_eq!(optional.take_tag(), NonZeroUsize::new(3));
+    /// assert_eq!(optional.get_tag(), None);
+    /// ```
+    fn take_tag(&mut self) -> M
+    where
+        M: Default,
+    {
+        self.replace_tag(M::default())
+    }
+
+    /// Reinterprets the raw tag bits of this FatRefMut as a different metadata type.
+    ///
+    /// Like for `FatRefExt::cast_meta`, the bits aren't checked against `N`.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut x = 5;
+    /// let fat = (&mut x).tag((1u32, 2u32)).cast_meta::<usize>();
+    /// assert_eq!(fat.get_tag(), 1 | 2 << 32);
+    /// ```
+    fn cast_meta<N: 'a + Metadata>(self) -> FatRefMut<'a, P, N> {
+        let raw = self.unsize.len();
+        unsafe { &mut *fat_ptr_mut(&mut self.pointee, raw) }
+    }
+
+    /// Converts the tag of this FatRefMut to a different metadata type with `f`.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut x = 5;
+    /// let fat = (&mut x).tag(1.5f32).retag(|f| (f * 2.0) as usize);
+    /// assert_eq!(fat.get_tag(), 3);
+    /// ```
+    fn retag<N: 'a + Metadata>(self, f: impl FnOnce(M) -> N) -> FatRefMut<'a, P, N> {
+        let tag = f(self.get_tag());
+        FatRefMut::from_ref_mut(self.to_plain_mut(), tag)
+    }
+
+    /// Turns this FatRefMut into a FatRefMut to a part of the pointee, keeping the tag.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// struct Inventory {
+    ///     items: Vec<u32>,
+    /// }
+    ///
+    /// let mut inv = Inventory { items: vec![1, 2] };
+    /// {
+    ///     let items = (&mut inv).tag('i').map_mut(|inv| &mut inv.items);
+    ///     assert_eq!(items.get_tag(), 'i');
+    ///     items.to_plain_mut().push(3);
+    /// }
+    /// assert_eq!(inv.items, [1, 2, 3]);
+    /// ```
+    ///
+    /// The original FatRefMut is used up by the projection:
+    /// ```compile_fail
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut pair = (1, 2);
+    /// let fat = (&mut pair).tag(0);
+    /// let first = fat.map_mut(|p| &mut p.0);
+    /// fat.get_tag();
+    /// first.get_tag();
+    /// ```
+    fn map_mut<U>(self, f: impl FnOnce(&mut P) -> &mut U) -> FatRefMut<'a, U, M> {
+        let raw = self.unsize.len();
+        unsafe { &mut *fat_ptr_mut(f(self.to_plain_mut()), raw) }
+    }
+
+    /// Like `map_mut`, but for projections that can fail.
+    ///
+    /// On failure, the original FatRefMut is given back.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut v = vec![1, 2];
+    ///
+    /// let first = (&mut v).tag('f').try_map_mut(|v| v.first_mut()).unwrap();
+    /// *first.to_plain_mut() = 10;
+    ///
+    /// let original = (&mut v).tag('x').try_map_mut(|v| v.get_mut(5)).unwrap_err();
+    /// assert_eq!(original.get_tag(), 'x');
+    /// original.to_plain_mut().push(3);
+    ///
+    /// assert_eq!(v, [10, 2, 3]);
+    /// ```
+    fn try_map_mut<U>(
+        self,
+        f: impl FnOnce(&mut P) -> Option<&mut U>,
+    ) -> Result<FatRefMut<'a, U, M>, Self> {
+        let raw = self.unsize.len();
+        let this: *mut FatPointee<P, M> = self;
+        // If `f` fails, nothing borrows from `this` anymore and it can be handed back.
+        match f(unsafe { &mut (*this).pointee }) {
+            Some(projected) => Ok(unsafe { &mut *fat_ptr_mut(projected, raw) }),
+            None => Err(unsafe { &mut *this }),
+        }
+    }
+
+    /// Splits this FatRefMut into FatRefMuts to two disjoint parts of the pointee.
+    ///
+    /// Both halves carry a copy of the original tag.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// struct Account {
+    ///     balance: i64,
+    ///     history: Vec<i64>,
+    /// }
+    ///
+    /// let mut acct = Account { balance: 0, history: vec![] };
+    /// {
+    ///     let fat = (&mut acct).tag('a');
+    ///     let (mut balance, history) = fat.map_split(|a| (&mut a.balance, &mut a.history));
+    ///     assert_eq!((balance.get_tag(), history.get_tag()), ('a', 'a'));
+    ///
+    ///     balance.set_tag('b');
+    ///     *balance.as_mut() += 10;
+    ///     history.as_mut().push(10);
+    ///     assert_eq!(history.get_tag(), 'a');
+    /// }
+    /// assert_eq!((acct.balance, acct.history), (10, vec![10]));
+    /// ```
+    fn map_split<U, V>(
+        self,
+        f: impl FnOnce(&mut P) -> (&mut U, &mut V),
+    ) -> (FatRefMut<'a, U, M>, FatRefMut<'a, V, M>) {
+        let raw = self.unsize.len();
+        let (u, v) = f(self.to_plain_mut());
+        unsafe { (&mut *fat_ptr_mut(u, raw), &mut *fat_ptr_mut(v, raw)) }
+    }
+
+    /// Returns a raw pointer to the pointee, read straight from the fat pointer.
+    fn as_ptr(&self) -> *const P {
+        &**self as *const FatPointee<P, M> as *const P
+    }
+
+    /// Returns a raw mutable pointer to the pointee without consuming this FatRefMut.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut x = 5;
+    /// let mut fat = (&mut x).tag(1);
+    /// let ptr = fat.as_mut_ptr();
+    /// unsafe { *ptr += 1 };
+    /// assert_eq!(fat.as_ptr(), ptr as *const i32);
+    /// assert_eq!(fat.addr(), ptr as usize);
+    /// assert_eq!(*fat.to_plain_mut(), 6);
+    /// ```
+    fn as_mut_ptr(&mut self) -> *mut P {
+        &mut **self as *mut FatPointee<P, M> as *mut P
+    }
+
+    /// Returns the address of the pointee.
+    fn addr(&self) -> usize {
+        self.as_ptr() as usize
+    }
+
+    /// Splits this FatRefMut into a raw mutable pointer to the pointee and the packed tag.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMut, FatRefMutExt};
+    /// let mut x = 1;
+    /// let (ptr, raw_tag) = (&mut x).tag(2).to_raw_parts_mut();
+    /// let fat: FatRefMut<i32, usize> = unsafe { FatRefMut::from_raw_parts_mut(ptr, raw_tag) };
+    /// *fat.as_mut() += fat.get_tag() as i32;
+    /// assert_eq!(x, 3);
+    /// ```
+    fn to_raw_parts_mut(mut self) -> (*mut P, usize) {
+        (self.as_mut_ptr(), self.unsize.len())
+    }
+
+    /// Makes a FatRefMut from a raw mutable pointer to the pointee and a packed tag.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be non-null, properly aligned and point to a valid `P`
+    /// that isn't accessed through any other pointer for the whole (caller-chosen) lifetime `'a`,
+    /// just like for `&mut *ptr`.
+    ///
+    /// As with `FatRefExt::from_raw_parts`, any `raw_tag` is fine.
+    unsafe fn from_raw_parts_mut(ptr: *mut P, raw_tag: usize) -> Self {
+        &mut *fat_ptr_mut(ptr, raw_tag)
+    }
+
+    /// Reinterprets the pointee as a `Q`, keeping the address and the tag.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMut, FatRefMutExt};
+    /// #[repr(transparent)]
+    /// struct Meters(f64);
+    ///
+    /// let mut distance = Meters(1.5);
+    /// let raw: FatRefMut<f64, char> = unsafe { (&mut distance).tag('m').cast_pointee() };
+    /// *raw.to_plain_mut() *= 2.0;
+    /// assert_eq!(distance.0, 3.0);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// `P` and `Q` must have an identical layout (size, alignment and valid bit patterns),
+    /// for example because one is a `#[repr(transparent)]` wrapper around the other.
+    unsafe fn cast_pointee<Q>(mut self) -> FatRefMut<'a, Q, M> {
+        &mut *fat_ptr_mut(self.as_mut_ptr() as *mut Q, self.unsize.len())
+    }
+
+    /// Sets the tag to `tag`, clamped to the largest value a `usize` can hold.
+    ///
+    /// See `FatRefExt::saturating_set_tag` for which metadata types this works with.
+    fn saturating_set_tag(&mut self, tag: M)
+    where
+        M: core::convert::TryInto<usize> + From<usize>,
+    {
+        let clamped = tag.try_into().unwrap_or(usize::MAX);
+        self.set_tag(M::from(clamped));
+    }
+
+    /// An alias for `get_tag`.
+    ///
+    /// See `FatRefExt::meta` for how it resolves next to the pointee's own methods.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut x = 5;
+    /// let mut fat = (&mut x).with_meta('a');
+    /// fat.set_meta('b');
+    /// assert_eq!(fat.meta(), 'b');
+    /// ```
+    fn meta(&self) -> M {
+        self.get_tag()
+    }
+
+    /// An alias for `set_tag`.
+    fn set_meta(&mut self, tag: M) {
+        self.set_tag(tag)
+    }
+
+    /// Calls `f` with the tag and returns this FatRefMut unchanged.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut pair = (1, 'x');
+    /// let mut seen = Vec::new();
+    /// {
+    ///     let mut fat = (&mut pair).tag(1).inspect_tag(|t| seen.push(*t));
+    ///     fat.update_tag(|t| t * 10);
+    ///     let second = fat
+    ///         .inspect(|p, t| assert_eq!((p.0, *t), (1, 10)))
+    ///         .map_mut(|p| &mut p.1)
+    ///         .inspect_tag(|t| seen.push(*t));
+    ///     *second.to_plain_mut() = 'y';
+    /// }
+    /// assert_eq!((pair, seen), ((1, 'y'), vec![1, 10]));
+    /// ```
+    fn inspect_tag(self, f: impl FnOnce(&M)) -> Self {
+        f(&self.get_tag());
+        self
+    }
+
+    /// Calls `f` with the pointee and the tag and returns this FatRefMut unchanged.
+    fn inspect(self, f: impl FnOnce(&P, &M)) -> Self {
+        f(&self.pointee, &self.get_tag());
+        self
+    }
+
+    /// Sets the tag of this FatRefMut, or returns an error if it doesn't fit.
+    ///
+    /// On error, the previous tag is left untouched.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// use std::time::Duration;
+    /// let mut x = 5;
+    /// let mut fat = (&mut x).tag(Duration::from_secs(2));
+    /// assert!(fat.try_set_tag(Duration::MAX).is_err());
+    /// assert_eq!(fat.try_replace_tag(Duration::from_secs(3)), Ok(Duration::from_secs(2)));
+    /// assert!(fat.try_replace_tag(Duration::MAX).is_err());
+    /// *fat.as_mut() += 1;
+    /// assert_eq!(fat.get_tag(), Duration::from_secs(3));
+    /// ```
+    fn try_set_tag(&mut self, tag: M) -> Result<(), TagOverflowError> {
+        let raw = try_pack_raw(tag)?;
+        let thin: *mut P = &mut self.pointee;
+        *self = unsafe { &mut *fat_ptr_mut(thin, raw) };
+        Ok(())
+    }
+
+    /// Replaces the tag of this FatRefMut and returns the old one, or returns an error if the new one doesn't fit.
+    ///
+    /// On error, the previous tag is left untouched.
+    fn try_replace_tag(&mut self, tag: M) -> Result<M, TagOverflowError> {
+        let old = self.get_tag();
+        self.try_set_tag(tag)?;
+        Ok(old)
+    }
+
+    /// Splits this binding into a mutable reference to the pointee and a handle to the tag.
+    ///
+    /// Changes made through the handle show up in this FatRefMut once both are gone.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// fn fill(v: &mut Vec<u8>) {
+    ///     v.extend_from_slice(b"abc");
+    /// }
+    ///
+    /// let mut v = Vec::new();
+    /// let mut fat = (&mut v).tag(0);
+    /// {
+    ///     let (pointee, mut tag) = fat.split_tag_mut();
+    ///     fill(pointee);
+    ///     tag.set(tag.get() + pointee.len());
+    ///     pointee.push(b'd');
+    ///     tag.set(tag.get() + 1);
+    /// }
+    /// assert_eq!(fat.get_tag(), 4);
+    /// assert_eq!(fat.as_ref(), b"abcd");
+    /// ```
+    ///
+    /// Neither half can outlive the split:
+    /// ```compile_fail
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut x = 5;
+    /// let mut fat = (&mut x).tag(0);
+    /// let (pointee, _tag) = fat.split_tag_mut();
+    /// fat.set_tag(1);
+    /// *pointee += 1;
+    /// ```
+    fn split_tag_mut(&mut self) -> (&mut P, TagProxy<'_, P, M>) {
+        let binding = self as *mut FatRefMut<'a, P, M> as *mut *mut FatPointee<P, M>;
+        // Only the pointer stored in the binding is read here, the binding is not reborrowed.
+        let pointee = unsafe { &mut *(*binding as *mut P) };
+        (pointee, TagProxy::new(binding))
+    }
+
+    /// Returns the raw bits of the tag, regardless of the metadata type.
+    fn raw_tag(&self) -> usize {
+        self.unsize.len()
+    }
+
+    /// Sets the raw bits of the tag, regardless of the metadata type.
+    ///
+    /// This bypasses `Metadata::pack`, so `get_tag` will give whatever `Metadata::unpack`
+    /// makes of these bits, see the `Metadata` docs.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut x = 5;
+    /// let mut fat = (&mut x).tag(1.0f32);
+    /// fat.set_raw_tag(2.0f32.to_bits() as usize);
+    /// *fat.as_mut() += 1;
+    /// assert_eq!((fat.get_tag(), fat.raw_tag()), (2.0, 0x4000_0000));
+    /// ```
+    fn set_raw_tag(&mut self, raw: usize) {
+        let thin: *mut P = &mut self.pointee;
+        *self = unsafe { &mut *fat_ptr_mut(thin, raw) };
+    }
+
+    /// Copies the raw tag of `other`, which may point at a different type, into this FatRefMut.
+    ///
+    /// The bits are copied without being unpacked, see the `Metadata` docs.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let (mut a, mut b) = (1u8, [2u16; 4]);
+    /// let source = (&mut a).tag(7);
+    /// let mut target = (&mut b).tag(0);
+    ///
+    /// target.copy_tag_from(&source);
+    /// assert_eq!((target.get_tag(), source.get_tag()), (7, 7));
+    /// ```
+    fn copy_tag_from<Q>(&mut self, other: &FatPointee<Q, M>) {
+        self.set_raw_tag(other.unsize.len());
+    }
+
+    /// Views the pointee as a transparent wrapper around it, keeping the tag.
+    ///
+    /// ```
+    /// # extern crate bytemuck;
+    /// # extern crate fat_pointer_hack;
+    /// # use fat_pointer_hack::{RefExt, FatRefMut, FatRefMutExt};
+    /// use bytemuck::TransparentWrapper;
+    ///
+    /// #[repr(transparent)]
+    /// struct Meters(f64);
+    /// unsafe impl TransparentWrapper<f64> for Meters {}
+    ///
+    /// # fn main() {
+    /// let mut distance = 1.5;
+    /// let meters: FatRefMut<Meters, usize> = (&mut distance).tag(1).wrap_pointee();
+    /// let peeled: FatRefMut<f64, usize> = meters.peel_pointee();
+    /// assert_eq!(peeled.get_tag(), 1);
+    /// *peeled.to_plain_mut() *= 2.0;
+    /// assert_eq!(distance, 3.0);
+    /// # }
+    /// ```
+    #[cfg(feature = "bytemuck")]
+    fn wrap_pointee<W: bytemuck::TransparentWrapper<P>>(self) -> FatRefMut<'a, W, M> {
+        // TransparentWrapper guarantees that W and P have the same layout.
+        unsafe { self.cast_pointee() }
+    }
+
+    /// Views the pointee, a transparent wrapper, as the type it wraps, keeping the tag.
+    #[cfg(feature = "bytemuck")]
+    fn peel_pointee<Inner>(self) -> FatRefMut<'a, Inner, M>
+    where
+        P: bytemuck::TransparentWrapper<Inner>,
+    {
+        // TransparentWrapper guarantees that P and Inner have the same layout.
+        unsafe { self.cast_pointee() }
+    }
+
+    /// Checks that the tag is the truncated hash of the pointee, as set by `HashTagExt::tag_hash`.
+    fn verify_tag<H: Hasher + Default>(&self) -> bool
+    where
+        P: Hash,
+        M: PartialEq<usize>,
+    {
+        self.get_tag() == hashtag::truncated_hash::<H, P>(&self.pointee)
+    }
+
+    /// Returns the raw length word of this binding, for changing the tag in place
+    /// without a round trip through `Metadata`.
+    ///
+    /// Only the binding (the fat pointer) is touched, never the pointee.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut x = 5;
+    /// let mut fat = (&mut x).tag(40usize);
+    /// unsafe {
+    ///     *fat.tag_word_mut() += 1;
+    ///     *fat.tag_word_mut() |= 1 << 1;
+    /// }
+    /// assert_eq!(fat.get_tag(), 43);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// Callers must only leave behind a word that `M::pack` could have produced.
+    ///
+    /// Any other bits aren't undefined behavior, since every `Metadata::unpack` has to accept them
+    /// (see the `Metadata` docs), but it's garbage in, garbage out: later reads of the tag
+    /// return whatever `unpack` makes of them, like `char::REPLACEMENT_CHARACTER` for invalid `char` bits.
+    unsafe fn tag_word_mut(&mut self) -> &mut usize {
+        // A `FatPointee` ends in `[()]`, so a FatRefMut has the same metadata as a `*mut [()]`,
+        // and it is made from one with an `as` cast (see `fat_ptr_mut`), which keeps the words as they are.
+        // That is why the word order found by `len_word_index` on a `*const [()]` applies here as well.
+        let words = self as *mut FatRefMut<'a, P, M> as *mut usize;
+        &mut *words.add(len_word_index())
+    }
+
+    /// Returns a non-null pointer to the pointee, dropping the tag.
+    ///
+    /// The pointer can be written through, like the FatRefMut it came from.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut x = 5;
+    /// let address = &x as *const i32;
+    /// let ptr = (&mut x).tag('p').as_non_null();
+    /// assert_eq!(ptr.as_ptr() as *const i32, address);
+    /// unsafe { *ptr.as_ptr() += 1 };
+    /// assert_eq!(x, 6);
+    /// ```
+    fn as_non_null(self) -> NonNull<P> {
+        NonNull::from(self.to_plain_mut())
+    }
+
+    /// Checks whether the tag satisfies `pred`.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut values = [1, 20, 3, 40];
+    /// for fat in values.iter_mut().map(|v| v.tag_by(|v| *v as usize)) {
+    ///     if fat.tag_matches(|t| t > 10) {
+    ///         *fat.to_plain_mut() = 0;
+    ///     }
+    /// }
+    /// assert_eq!(values, [1, 0, 3, 0]);
+    /// ```
+    fn tag_matches(&self, pred: impl FnOnce(M) -> bool) -> bool {
+        pred(self.get_tag())
+    }
+}
+
+/// Makes a FatRef from a given reference and a tag.
+///
+/// This is the same as `FatRef::from_ref` or `(&x).tag(tag)`, but it can't be confused
+/// with a `tag` method of the pointee:
+/// ```
+/// use fat_pointer_hack::prelude::*;
+///
+/// struct Label;
+/// impl Label {
+///     fn tag(&self, _: usize) -> &'static str {
+///         "label"
+///     }
+/// }
+///
+/// let label = Label;
+/// assert_eq!((&label).tag(1), "label");
+/// assert_eq!(tagged(&label, 1).get_tag(), 1);
+/// ```
+///
+/// The FatRef can't outlive the reference it was made from:
+/// ```compile_fail
+/// # use fat_pointer_hack::{tagged, FatRef};
+/// let fat: FatRef<'static, i32, usize> = {
+///     let x = 5;
+///     tagged(&x, 1)
+/// };
+/// ```
+pub fn tagged<P, M: Metadata>(thin_ref: &P, tag: M) -> FatRef<'_, P, M> {
+    FatRef::from_ref(thin_ref, tag)
+}
+
+/// Makes a FatRefMut from a given mutable reference and a tag.
+///
+/// ```
+/// use fat_pointer_hack::prelude::*;
+///
+/// let mut x = 5;
+/// *tagged_mut(&mut x, 'm').as_mut() += 1;
+/// assert_eq!(x, 6);
+/// ```
+pub fn tagged_mut<P, M: Metadata>(thin_ref: &mut P, tag: M) -> FatRefMut<'_, P, M> {
+    FatRefMut::from_ref_mut(thin_ref, tag)
+}
+
+/// Calls `f` with a tagged view of `thin_ref` and returns its result together with the final tag.
+///
+/// `f` may change the tag, the returned tag reflects that.
+/// ```
+/// # use fat_pointer_hack::{FatRefExt, with_tagged};
+/// let x = 5;
+/// let (len, tag) = with_tagged(&x, 'a', |fat| {
+///     let (inner, inner_tag) = with_tagged(fat.to_plain(), 1, |inner| {
+///         inner.update_tag(|t| t + 1);
+///         inner.get_tag()
+///     });
+///     assert_eq!((inner, inner_tag), (2, 2));
+///     fat.set_tag('b');
+///     *fat.to_plain() + inner
+/// });
+/// assert_eq!((len, tag), (7, 'b'));
+/// ```
+pub fn with_tagged<P, M: Metadata, R>(
+    thin_ref: &P,
+    tag: M,
+    f: impl FnOnce(&mut FatRef<'_, P, M>) -> R,
+) -> (R, M) {
+    let mut fat = FatRef::from_ref(thin_ref, tag);
+    let result = f(&mut fat);
+    (result, fat.get_tag())
+}
+
+/// Calls `f` with a mutable tagged view of `thin_ref` and returns its result together with the final tag.
+///
+/// ```
+/// # use fat_pointer_hack::{FatRefMutExt, with_tagged_mut};
+/// let mut v = vec![1];
+/// let ((), tag) = with_tagged_mut(&mut v, 0, |fat| {
+///     fat.as_mut().push(2);
+///     fat.set_tag(fat.as_ref().len());
+/// });
+/// assert_eq!((v, tag), (vec![1, 2], 2));
+/// ```
+pub fn with_tagged_mut<P, M: Metadata, R>(
+    thin_ref: &mut P,
+    tag: M,
+    f: impl FnOnce(&mut FatRefMut<'_, P, M>) -> R,
+) -> (R, M) {
+    let mut fat = FatRefMut::from_ref_mut(thin_ref, tag);
+    let result = f(&mut fat);
+    (result, fat.get_tag())
+}
+
+/// Converts `value` into a raw tag of `width` bits (at most the width of a `usize`),
+/// or returns an error if it doesn't fit.
+///
+/// Passing a smaller width than that of a `usize` shows what would happen on a smaller target:
+/// ```
+/// # use fat_pointer_hack::pack_to_width;
+/// assert_eq!(pack_to_width(u32::MAX as u128, 32), Ok(u32::MAX as usize));
+///
+/// let err = pack_to_width(1 << 40, 32).unwrap_err();
+/// assert_eq!((err.value_width(), err.type_width()), (Some(41), None));
+/// assert_eq!(err.to_string(), "a 41-bit value does not fit into the tag");
+///
+/// assert!(pack_to_width(u128::MAX, 128).is_err());
+/// ```
+pub fn pack_to_width(value: u128, width: u32) -> Result<usize, TagOverflowError> {
+    if value == saturate_to_width(value, width) as u128 {
+        Ok(value as usize)
+    } else {
+        Err(TagOverflowError::new(128 - value.leading_zeros()))
+    }
+}
+
+/// Clamps `value` to the largest number that fits into `width` bits (and into a `usize`).
+///
+/// With the width of a `usize` this is what `saturating_set_tag` stores,
+/// passing a smaller width shows what it would store on a smaller target:
+/// ```
+/// # use fat_pointer_hack::saturate_to_width;
+/// assert_eq!(saturate_to_width(5, 32), 5);
+/// assert_eq!(saturate_to_width(u32::MAX as u128, 32), u32::MAX as usize);
+/// assert_eq!(saturate_to_width(u32::MAX as u128 + 1, 32), u32::MAX as usize);
+/// assert_eq!(saturate_to_width(1 << 40, 32), u32::MAX as usize);
+/// assert_eq!(saturate_to_width(u128::MAX, 128), usize::MAX);
+/// assert_eq!(saturate_to_width(1, 0), 0);
+/// ```
+pub fn saturate_to_width(value: u128, width: u32) -> usize {
+    let width = width.min(usize::BITS);
+    let max = u128::MAX.checked_shr(128 - width).unwrap_or(0);
+    value.min(max) as usize
+}
+
+/// Makes a FatRef with a `usize` tag in a `const` context.
+///
+/// Unlike `FatRef::from_ref` this is a `const fn`, so it can be used to build statics.
+/// ```
+/// # use fat_pointer_hack::{FatRef, FatRefExt, fat_ref_const, tag_const};
+/// static CONFIG: u32 = 5;
+/// static TAGGED: FatRef<'static, u32, usize> = fat_ref_const(&CONFIG, 7);
+/// static TABLE: [FatRef<'static, u32, usize>; 2] = [fat_ref_const(&CONFIG, 1), fat_ref_const(&CONFIG, 2)];
+/// const TAG: usize = tag_const(TAGGED);
+///
+/// assert_eq!(*TAGGED.to_plain(), 5);
+/// assert_eq!(TAG, 7);
+/// assert_eq!(TABLE.map(|fat| fat.get_tag()), [1, 2]);
+/// ```
+pub const fn fat_ref_const<P>(thin_ref: &P, tag: usize) -> FatRef<'_, P, usize> {
+    unsafe { &*fat_ptr(thin_ref, tag) }
+}
+
+/// Returns the tag of a FatRef with a `usize` tag in a `const` context.
+pub const fn tag_const<P>(fat: FatRef<'_, P, usize>) -> usize {
+    fat.unsize.len()
 }
 
+/// Swaps the tags of two FatRefs, leaving their pointees where they are.
+///
+/// The raw tag bits are swapped directly, so neither tag is unpacked.
+/// ```
+/// # use fat_pointer_hack::{RefExt, FatRefExt, swap_tags};
+/// let (x, y) = (1, 2);
+/// let mut a = (&x).tag('a');
+/// let mut b = (&y).tag('b');
+///
+/// swap_tags(&mut a, &mut b);
+/// assert_eq!((a.to_plain(), a.get_tag()), (&1, 'b'));
+/// assert_eq!((b.to_plain(), b.get_tag()), (&2, 'a'));
+///
+/// // Both may point at the same value too.
+/// let mut c = (&x).tag('c');
+/// swap_tags(&mut a, &mut c);
+/// assert_eq!((a.get_tag(), c.get_tag()), ('c', 'b'));
+/// ```
+pub fn swap_tags<'a, 'b, P, M>(a: &mut FatRef<'a, P, M>, b: &mut FatRef<'b, P, M>) {
+    let (raw_a, raw_b) = (a.unsize.len(), b.unsize.len());
+    *a = unsafe { &*fat_ptr(&a.pointee, raw_b) };
+    *b = unsafe { &*fat_ptr(&b.pointee, raw_a) };
+}
+
+/// Swaps the tags of two FatRefMuts, leaving their pointees where they are.
+///
+/// ```
+/// # use fat_pointer_hack::{RefExt, FatRefMutExt, swap_tags_mut};
+/// let (mut x, mut y) = (1, 2);
+/// let mut a = (&mut x).tag('a');
+/// let mut b = (&mut y).tag('b');
+///
+/// swap_tags_mut(&mut a, &mut b);
+/// assert_eq!(a.get_tag(), 'b');
+/// assert_eq!(b.get_tag(), 'a');
+/// ```
+pub fn swap_tags_mut<'a, 'b, P, M>(a: &mut FatRefMut<'a, P, M>, b: &mut FatRefMut<'b, P, M>) {
+    let (raw_a, raw_b) = (a.unsize.len(), b.unsize.len());
+    *a = unsafe { &mut *fat_ptr_mut(&mut a.pointee, raw_b) };
+    *b = unsafe { &mut *fat_ptr_mut(&mut b.pointee, raw_a) };
+}
+
+pub mod prelude;
+
+mod error;
+/// The error for tags that don't fit.
+pub use error::TagOverflowError;
+
 mod refext;
 /// An extension trait that adds a `.tag()` method to all regular references.
 pub use refext::RefExt;
 
+mod array;
+/// An extension trait that adds a `.tag_each()` method to references to arrays.
+pub use array::ArrayRefExt;
+
+mod cmp;
+/// A trait for the other side of tagged comparisons.
+pub use cmp::TaggedCmp;
+
+mod iter;
+/// An extension trait for tagging the references yielded by iterators.
+pub use iter::{TagEnumerate, TagIterExt, TagWith};
+
+mod slice;
+/// An extension trait for iterating over slices with index tags.
+pub use slice::SliceTagExt;
+/// Helpers for sorting, grouping and searching slices of FatRefs by tag.
+pub use slice::{group_by_tag, max_by_tag, min_by_tag, sort_unstable_by_tag};
+#[cfg(feature = "alloc")]
+pub use slice::sort_by_tag;
+
+mod pinned;
+/// An extension trait for tagging pinned references.
+pub use pinned::PinnedFatRefExt;
+
+mod asfat;
+/// Traits for everything that can be borrowed as a fat reference.
+pub use asfat::{AsFatRef, AsFatRefMut};
+
+mod tagproxy;
+/// A handle to the tag of a FatRefMut whose pointee is borrowed elsewhere.
+pub use tagproxy::TagProxy;
+
+mod tagguard;
+/// A guard that restores the previous tag of a FatRef when dropped.
+pub use tagguard::TagGuard;
+
+mod fatoption;
+/// Helpers for optional fat references.
+pub use fatoption::FatOption;
+
+mod pairref;
+/// Two references packed into one fat pointer.
+pub use pairref::PairRef;
+
+mod bitfloat;
+/// A float tag with bitwise equality and hashing.
+pub use bitfloat::BitEqFloat;
+
+mod dynref;
+/// A tagged reference to an unsized value, with the tag stored next to the pointer.
+pub use dynref::DynFatRef;
+
+mod typetag;
+/// A compact type code tag for type-erased references.
+pub use typetag::{TypeTag, TypeTagged};
+
+mod smallstr;
+/// A short string that fits into a tag.
+pub use smallstr::SmallStr;
+
+#[cfg(tag_width_64)]
+mod generation;
+/// A slot and generation tag for detecting stale references.
+#[cfg(tag_width_64)]
+pub use generation::{Generation, GenerationRefExt};
+
+#[cfg(tag_width_64)]
+mod lanes;
+/// Tags made of several smaller values side by side.
+#[cfg(tag_width_64)]
+pub use lanes::{CharPair, Pair, Quad};
+
+mod hashtag;
+/// Tagging references with a hash of their pointee.
+pub use hashtag::HashTagExt;
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
+
+#[cfg(feature = "alloc")]
+mod fatvec;
+/// A buffer of values that are handed out as tagged references.
+#[cfg(feature = "alloc")]
+pub use fatvec::{FatVec, FatVecIter};
+
+#[cfg(feature = "alloc")]
+mod fatarc;
+/// A reference-counted pointer that carries a tag per handle.
+#[cfg(feature = "alloc")]
+pub use fatarc::FatArc;
+
+impl<P, M: Metadata> FatPointee<P, M> {
+    /// Returns a reference to the pointee together with the tag.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::RefExt;
+    /// let x = 5;
+    /// let fat = (&x).tag('t');
+    /// assert_eq!(fat.as_ref_tagged(), (&5, 't'));
+    /// ```
+    pub fn as_ref_tagged(&self) -> (&P, M) {
+        (&self.pointee, M::unpack(Tag(self.unsize.len())))
+    }
+
+    /// Returns a mutable reference to the pointee together with the tag.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::RefExt;
+    /// let mut x = 5;
+    /// {
+    ///     let fat = (&mut x).tag(2);
+    ///     let (value, tag) = fat.as_mut_tagged();
+    ///     *value *= tag;
+    /// }
+    /// assert_eq!(x, 10);
+    /// ```
+    pub fn as_mut_tagged(&mut self) -> (&mut P, M) {
+        let tag = M::unpack(Tag(self.unsize.len()));
+        (&mut self.pointee, tag)
+    }
+}
+
 impl<P, M> core::convert::AsRef<P> for FatPointee<P, M> {
     fn as_ref(&self) -> &P {
         &self.pointee
@@ -276,7 +2857,291 @@ impl<P, M> core::convert::AsMut<P> for FatPointee<P, M> {
     }
 }
 
+/// Two fat references are equal if their pointees are equal and their tags are equal.
+///
+/// Where they point doesn't matter, use `FatRefExt::ptr_eq` for that.
+/// ```
+/// # use fat_pointer_hack::{RefExt, BitEqFloat};
+/// use std::collections::HashSet;
+///
+/// let (a, b) = (String::from("key"), String::from("key"));
+/// let other = String::from("other");
+/// assert_eq!((&a).tag('t'), (&b).tag('t'));
+/// assert_ne!((&a).tag('t'), (&b).tag('u'));
+/// assert_ne!((&a).tag('t'), (&other).tag('t'));
+///
+/// let set: HashSet<_> = vec![(&a).tag('t'), (&b).tag('t'), (&a).tag('u')].into_iter().collect();
+/// assert_eq!(set.len(), 2);
+///
+/// // PartialEq follows the tag type, so a NaN tag makes a reference unequal to itself.
+/// let nan = (&a).tag(f64::NAN);
+/// assert_ne!(nan, nan);
+/// let bitwise = (&a).tag(BitEqFloat(f64::NAN));
+/// assert_eq!(bitwise, bitwise);
+/// ```
+///
+/// `Eq` needs both the pointee and the tag to be `Eq`, so float tags don't get it:
+/// ```compile_fail
+/// # use fat_pointer_hack::{FatRef, RefExt};
+/// fn assert_eq_impl<T: Eq + ?Sized>(_: &T) {}
+/// let x = 5;
+/// let fat: FatRef<i32, f64> = (&x).tag(1.5);
+/// assert_eq_impl(fat);
+/// ```
+impl<P: PartialEq, M: Metadata + PartialEq> PartialEq for FatPointee<P, M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.pointee == other.pointee
+            && M::unpack(Tag(self.unsize.len())) == M::unpack(Tag(other.unsize.len()))
+    }
+}
+
+impl<P: Eq, M: Metadata + Eq> Eq for FatPointee<P, M> {}
+
+/// Fat references are ordered by pointee first, then by tag to break ties.
+///
+/// This is the same ordering as `FatRefExt::cmp_value_primary`, and consistent with `PartialEq`.
+/// ```
+/// # use fat_pointer_hack::{FatRef, FatRefExt, RefExt};
+/// use std::cmp::Ordering;
+///
+/// let values = [3u32, 1, 2, 1];
+/// let mut refs: Vec<FatRef<u32, char>> = vec![
+///     (&values[0]).tag('a'),
+///     (&values[1]).tag('z'),
+///     (&values[2]).tag('b'),
+///     (&values[3]).tag('c'),
+/// ];
+/// refs.sort();
+/// let sorted: Vec<_> = refs.iter().map(|r| (*r.to_plain(), r.get_tag())).collect();
+/// assert_eq!(sorted, [(1, 'c'), (1, 'z'), (2, 'b'), (3, 'a')]);
+///
+/// for a in &refs {
+///     for b in &refs {
+///         assert_eq!(a.cmp(b) == Ordering::Equal, a == b);
+///         assert_eq!(a.partial_cmp(b), Some(a.cmp(b)));
+///     }
+/// }
+///
+/// let (one, nan) = (1.0, f64::NAN);
+/// assert_eq!((&one).tag('a').partial_cmp((&nan).tag('a')), None);
+/// assert!((&one).tag('a') < (&one).tag('b'));
+/// ```
+impl<P: PartialOrd, M: Metadata + PartialOrd> PartialOrd for FatPointee<P, M> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.pointee.partial_cmp(&other.pointee) {
+            Some(Ordering::Equal) => {
+                M::unpack(Tag(self.unsize.len())).partial_cmp(&M::unpack(Tag(other.unsize.len())))
+            }
+            ordering => ordering,
+        }
+    }
+}
+
+impl<P: Ord, M: Metadata + Ord> Ord for FatPointee<P, M> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.pointee.cmp(&other.pointee).then_with(|| {
+            M::unpack(Tag(self.unsize.len())).cmp(&M::unpack(Tag(other.unsize.len())))
+        })
+    }
+}
+
+/// Hashes the pointee first, then the unpacked tag.
+///
+/// So two fat references hash the same if their pointees do and their tags do,
+/// consistent with `PartialEq`. The address is not hashed.
+/// ```
+/// # use fat_pointer_hack::{RefExt, BitEqFloat};
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::hash::{Hash, Hasher};
+///
+/// fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+///     let mut hasher = DefaultHasher::new();
+///     value.hash(&mut hasher);
+///     hasher.finish()
+/// }
+///
+/// let (a, b) = (String::from("key"), String::from("key"));
+/// assert_eq!(hash_of((&a).tag('t')), hash_of((&b).tag('t')));
+/// assert_ne!(hash_of((&a).tag('t')), hash_of((&b).tag('u')));
+/// assert_eq!(hash_of((&a).tag('t')), hash_of(&(&a, 't')));
+///
+/// let mut c = String::from("key");
+/// assert_eq!(hash_of(&*(&mut c).tag(1usize)), hash_of((&a).tag(1usize)));
+/// assert_eq!(hash_of((&a).tag(BitEqFloat(f64::NAN))), hash_of((&b).tag(BitEqFloat(f64::NAN))));
+/// ```
+impl<P: Hash, M: Metadata + Hash> Hash for FatPointee<P, M> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.pointee.hash(state);
+        M::unpack(Tag(self.unsize.len())).hash(state);
+    }
+}
+
+/// FatRefs deref to their pointee, so pointee methods and fields can be used directly.
+///
+/// ```
+/// # use fat_pointer_hack::{RefExt, FatRefExt};
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// let name = String::from("origin");
+/// let fat = (&name).tag(1usize);
+/// assert_eq!(fat.len(), 6);
+/// assert!(fat.starts_with("or"));
+///
+/// let point = Point { x: 3, y: 4 };
+/// let fat = (&point).tag('p');
+/// assert_eq!(fat.x + fat.y, 7);
+///
+/// let plain: &Point = &*fat;
+/// assert_eq!(plain.y, 4);
+/// assert_eq!(fat.get_tag(), 'p');
+/// ```
+///
+/// Methods of the extension traits, like `get_tag`, take precedence over pointee methods
+/// of the same name. Deref explicitly to reach those instead:
+/// ```
+/// # use fat_pointer_hack::{RefExt, FatRefExt};
+/// struct Label;
+/// impl Label {
+///     fn get_tag(&self) -> &'static str {
+///         "label"
+///     }
+/// }
+///
+/// let label = Label;
+/// let fat = (&label).tag(3usize);
+/// assert_eq!(fat.get_tag(), 3);
+/// assert_eq!((**fat).get_tag(), "label");
+/// ```
+impl<P, M> core::ops::Deref for FatPointee<P, M> {
+    type Target = P;
+    fn deref(&self) -> &P {
+        &self.pointee
+    }
+}
+
+/// FatRefMuts deref mutably to their pointee, for `&mut self` methods, assignment and reborrows.
+///
+/// ```
+/// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+/// let mut v = vec![1, 2];
+/// {
+///     let fat = (&mut v).tag('v');
+///     fat.push(3);
+///     fat[0] = 0;
+///     let plain: &mut Vec<i32> = &mut *fat;
+///     plain.push(4);
+///     assert_eq!(fat.get_tag(), 'v');
+/// }
+/// assert_eq!(v, [0, 2, 3, 4]);
+///
+/// let mut x = 5;
+/// {
+///     let fat = (&mut x).tag(1usize);
+///     **fat = 7;
+///     **fat += 1;
+/// }
+/// assert_eq!(x, 8);
+/// ```
+///
+/// A shared FatRef only gives shared access:
+/// ```compile_fail
+/// # use fat_pointer_hack::RefExt;
+/// let v = vec![1, 2];
+/// let fat = (&v).tag('v');
+/// fat.push(3);
+/// ```
+impl<P, M> core::ops::DerefMut for FatPointee<P, M> {
+    fn deref_mut(&mut self) -> &mut P {
+        &mut self.pointee
+    }
+}
+
+/// Iterating over a FatRef iterates over its pointee, the tag is not part of the items.
+///
+/// ```
+/// # use fat_pointer_hack::RefExt;
+/// let xs = [1, 2, 3];
+/// let fat_ref = (&xs).tag(7);
+///
+/// let mut sum = 0;
+/// for x in fat_ref {
+///     sum += x;
+/// }
+/// assert_eq!(sum, 6);
+/// ```
+impl<'a, P, M> IntoIterator for &'a FatPointee<P, M>
+where
+    &'a P: IntoIterator,
+{
+    type Item = <&'a P as IntoIterator>::Item;
+    type IntoIter = <&'a P as IntoIterator>::IntoIter;
+    fn into_iter(self) -> Self::IntoIter {
+        (&self.pointee).into_iter()
+    }
+}
+
+/// `fat_ref += n` adds to the tag, *not* to the pointee. It wraps around on overflow.
+///
+/// Only `usize` tags get these operators. `usize` is the only integer type that implements `Metadata`,
+/// so that an integer literal like in `(&x).tag(3)` is never ambiguous.
+///
+/// ```
+/// # use fat_pointer_hack::{RefExt, FatRefExt};
+/// let x = 5;
+/// let mut counted = (&x).tag(usize::MAX - 1);
+/// counted += 3;
+/// assert_eq!(counted.get_tag(), 1);
+/// assert_eq!(*counted.to_plain(), 5);
+///
+/// counted -= 2;
+/// assert_eq!(counted.get_tag(), usize::MAX);
+/// ```
+impl<'a, P> core::ops::AddAssign<usize> for FatRef<'a, P, usize> {
+    fn add_assign(&mut self, n: usize) {
+        self.update_tag(|tag| tag.wrapping_add(n));
+    }
+}
+
+/// `fat_ref -= n` subtracts from the tag, *not* from the pointee. It wraps around on overflow.
+impl<'a, P> core::ops::SubAssign<usize> for FatRef<'a, P, usize> {
+    fn sub_assign(&mut self, n: usize) {
+        self.update_tag(|tag| tag.wrapping_sub(n));
+    }
+}
+
+/// `fat_mut_ref += n` adds to the tag, *not* to the pointee. It wraps around on overflow.
+///
+/// ```
+/// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+/// let mut x = 5;
+/// let mut counted = (&mut x).tag(0);
+/// counted -= 1;
+/// assert_eq!(counted.get_tag(), usize::MAX);
+/// counted += 1;
+/// assert_eq!(counted.get_tag(), 0);
+/// *counted.as_mut() += 1;
+/// assert_eq!(x, 6);
+/// ```
+impl<'a, P> core::ops::AddAssign<usize> for FatRefMut<'a, P, usize> {
+    fn add_assign(&mut self, n: usize) {
+        self.update_tag(|tag| tag.wrapping_add(n));
+    }
+}
+
+/// `fat_mut_ref -= n` subtracts from the tag, *not* from the pointee. It wraps around on overflow.
+impl<'a, P> core::ops::SubAssign<usize> for FatRefMut<'a, P, usize> {
+    fn sub_assign(&mut self, n: usize) {
+        self.update_tag(|tag| tag.wrapping_sub(n));
+    }
+}
+
 use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+use core::cmp::Ordering;
+use core::ptr::NonNull;
 
 impl<P: Debug, M: Debug + Metadata> Debug for FatPointee<P, M> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -0,0 +1,145 @@
+/// Makes a fat reference by tagging a regular reference.
+///
+/// `fat_ref!(r, tag)` makes a `FatRef`, `fat_ref!(mut r, tag)` makes a `FatRefMut`.
+/// The metadata type is inferred from the tag, or can be spelled out with `=> Type`.
+/// ```
+/// # #[macro_use] extern crate fat_pointer_hack;
+/// # use fat_pointer_hack::{FatRef, FatRefExt, FatRefMutExt};
+/// # fn main() {
+/// let x = 5;
+/// let shared = fat_ref!(&x, 'a');
+/// assert_eq!(shared.get_tag(), 'a');
+///
+/// let annotated = fat_ref!(&x, 42 => usize);
+/// assert_eq!(annotated.get_tag(), 42);
+///
+/// let mut y = 1;
+/// {
+///     let exclusive = fat_ref!(mut &mut y, 0.5 => f32);
+///     *exclusive.as_mut() += 1;
+///     assert_eq!(exclusive.get_tag(), 0.5);
+/// }
+/// assert_eq!(y, 2);
+///
+/// // Without `mut`, even a mutable reference is tagged as a shared one.
+/// let downgraded: FatRef<_, _> = fat_ref!(&mut y, 'd');
+/// assert_eq!(downgraded.to_plain(), &2);
+/// # }
+/// ```
+///
+/// The usual borrowing rules apply:
+/// ```compile_fail
+/// # #[macro_use] extern crate fat_pointer_hack;
+/// # use fat_pointer_hack::FatRefExt;
+/// # fn main() {
+/// let mut v = vec![1];
+/// let fat = fat_ref!(&v, 0);
+/// v.push(2);
+/// fat.get_tag();
+/// # }
+/// ```
+#[macro_export]
+macro_rules! fat_ref {
+    (mut $r:expr, $tag:expr => $meta:ty) => {
+        $crate::RefExt::<$meta>::tag(&mut *$r, $tag)
+    };
+    (mut $r:expr, $tag:expr) => {
+        $crate::RefExt::tag(&mut *$r, $tag)
+    };
+    ($r:expr, $tag:expr => $meta:ty) => {
+        $crate::RefExt::<$meta>::tag(&*$r, $tag)
+    };
+    ($r:expr, $tag:expr) => {
+        $crate::RefExt::tag(&*$r, $tag)
+    };
+}
+
+/// Projects a `FatRef` through a chain of fields and constant indices, keeping its tag.
+///
+/// `project!(fat => .a.b[0].1)` is shorthand for `fat.map(|p| &p.a.b[0].1)`.
+/// ```
+/// # #[macro_use] extern crate fat_pointer_hack;
+/// # use fat_pointer_hack::{FatRef, FatRefExt};
+/// # fn main() {
+/// struct Inner { items: [(u8, char); 2] }
+/// struct Outer { inner: Inner }
+///
+/// let outer = Outer { inner: Inner { items: [(1, 'x'), (2, 'y')] } };
+/// let fat: FatRef<Outer, usize> = FatRef::from_ref(&outer, 7);
+/// let c = project!(fat => .inner.items[1].1);
+/// assert_eq!(*c.to_plain(), 'y');
+/// assert_eq!(c.get_tag(), 7);
+/// # }
+/// ```
+///
+/// Segments that don't exist are reported by the compiler as usual:
+/// ```compile_fail
+/// # #[macro_use] extern crate fat_pointer_hack;
+/// # use fat_pointer_hack::{FatRef, FatRefExt};
+/// # fn main() {
+/// struct Outer { inner: u8 }
+/// let outer = Outer { inner: 1 };
+/// let fat: FatRef<Outer, usize> = FatRef::from_ref(&outer, 7);
+/// let _ = project!(fat => .missing);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! project {
+    ($fat:expr => $($path:tt)+) => {
+        $crate::FatRefExt::map($fat, |pointee| &pointee $($path)+)
+    };
+}
+
+/// Projects a `FatRefMut` through a chain of fields and constant indices, keeping its tag.
+///
+/// `project_mut!(fat => .a.b[0].1)` is shorthand for `fat.map_mut(|p| &mut p.a.b[0].1)`.
+/// ```
+/// # #[macro_use] extern crate fat_pointer_hack;
+/// # use fat_pointer_hack::{FatRefMut, FatRefMutExt};
+/// # fn main() {
+/// struct Outer { inner: (u8, [u8; 3]) }
+///
+/// let mut outer = Outer { inner: (0, [1, 2, 3]) };
+/// {
+///     let fat: FatRefMut<Outer, char> = FatRefMut::from_ref_mut(&mut outer, 'm');
+///     let last = project_mut!(fat => .inner.1[2]);
+///     assert_eq!(last.get_tag(), 'm');
+///     *last.to_plain_mut() = 30;
+/// }
+/// assert_eq!(outer.inner.1, [1, 2, 30]);
+/// # }
+/// ```
+///
+/// The input is consumed, so it can't be used alongside the projection:
+/// ```compile_fail
+/// # #[macro_use] extern crate fat_pointer_hack;
+/// # use fat_pointer_hack::{FatRefMut, FatRefMutExt};
+/// # fn main() {
+/// let mut pair = (1u8, 2u8);
+/// let fat: FatRefMut<(u8, u8), usize> = FatRefMut::from_ref_mut(&mut pair, 0);
+/// let first = project_mut!(fat => .0);
+/// fat.get_tag();
+/// first.get_tag();
+/// # }
+/// ```
+#[macro_export]
+macro_rules! project_mut {
+    ($fat:expr => $($path:tt)+) => {
+        $crate::FatRefMutExt::map_mut($fat, |pointee| &mut pointee $($path)+)
+    };
+}
+
+/// Assigns `TypeTag` codes to types.
+///
+/// `type_tags! { Foo = 1, Bar = 2 }` implements `TypeTagged` for `Foo` and `Bar`.
+/// See `TypeTag` for an example.
+#[macro_export]
+macro_rules! type_tags {
+    ($($ty:ty = $code:expr),* $(,)*) => {
+        $(
+            impl $crate::TypeTagged for $ty {
+                const TYPE_TAG: $crate::TypeTag = $crate::TypeTag($code);
+            }
+        )*
+    };
+}
@@ -0,0 +1,111 @@
+use core::fmt;
+
+use super::{FatRef, FatRefExt, RefExt};
+
+/// Two references in one fat pointer: the first in the data half,
+/// the address of the second in the tag.
+///
+/// Both references share the lifetime `'a`, so neither can dangle while the PairRef is around.
+/// ```
+/// # use fat_pointer_hack::PairRef;
+/// use std::mem::size_of;
+///
+/// let name = String::from("answer");
+/// let value = 42u64;
+/// let pair = PairRef::new(&name, &value);
+///
+/// assert_eq!(size_of::<PairRef<String, u64>>(), 2 * size_of::<usize>());
+/// assert_eq!(pair.first(), "answer");
+/// assert_eq!(*pair.second(), 42);
+///
+/// let (first, second): (&String, &u64) = pair.into_parts();
+/// assert_eq!((first.len(), *second), (6, 42));
+/// ```
+///
+/// The second reference can't outlive its referent any more than the first can:
+/// ```compile_fail
+/// # use fat_pointer_hack::PairRef;
+/// let name = "answer";
+/// let pair;
+/// {
+///     let value = 42;
+///     pair = PairRef::new(&name, &value);
+/// }
+/// assert_eq!(*pair.second(), 42);
+/// ```
+///
+/// Like a `(&A, &B)`, a PairRef can be sent to another thread if both pointees are `Sync`:
+/// ```
+/// # use fat_pointer_hack::PairRef;
+/// static NAME: &str = "answer";
+/// static VALUE: u64 = 42;
+///
+/// let pair = PairRef::new(&NAME, &VALUE);
+/// let seen = std::thread::spawn(move || (*pair.first(), *pair.second())).join().unwrap();
+/// assert_eq!(seen, ("answer", 42));
+/// assert_eq!(format!("{:?}", pair), r#"PairRef { first: "answer", second: 42 }"#);
+/// ```
+///
+/// But not if one of them isn't:
+/// ```compile_fail
+/// # use fat_pointer_hack::PairRef;
+/// use std::cell::Cell;
+///
+/// static NAME: &str = "answer";
+/// let value = Box::leak(Box::new(Cell::new(42)));
+/// let pair = PairRef::new(&NAME, value);
+/// std::thread::spawn(move || pair.second().get());
+/// ```
+pub struct PairRef<'a, A: 'a, B: 'a> {
+    fat: FatRef<'a, A, *const B>,
+}
+
+impl<'a, A: 'a, B: 'a> PairRef<'a, A, B> {
+    /// Packs two references into one fat pointer.
+    pub fn new(first: &'a A, second: &'a B) -> Self {
+        PairRef {
+            fat: first.tag(second as *const B),
+        }
+    }
+
+    /// Returns the reference stored in the data half.
+    pub fn first(&self) -> &'a A {
+        self.fat.to_plain()
+    }
+
+    /// Returns the reference stored in the tag.
+    pub fn second(&self) -> &'a B {
+        // The tag was made from a `&'a B` in `new` and can't be changed since.
+        unsafe { &*self.fat.get_tag() }
+    }
+
+    /// Splits this PairRef back into the two references.
+    pub fn into_parts(self) -> (&'a A, &'a B) {
+        (self.first(), self.second())
+    }
+}
+
+impl<'a, A: 'a, B: 'a> Clone for PairRef<'a, A, B> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, A: 'a, B: 'a> Copy for PairRef<'a, A, B> {}
+
+impl<'a, A: fmt::Debug + 'a, B: fmt::Debug + 'a> fmt::Debug for PairRef<'a, A, B> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PairRef")
+            .field("first", self.first())
+            .field("second", self.second())
+            .finish()
+    }
+}
+
+// SAFETY: a PairRef is only a `&'a A` and a `&'a B`, the second one stored as a `*const B` in the tag,
+// which is what keeps the auto traits from being implemented. It hands out nothing but the two shared
+// references, so it is `Send` and `Sync` under the same conditions as a `(&'a A, &'a B)`.
+unsafe impl<'a, A: Sync + 'a, B: Sync + 'a> Send for PairRef<'a, A, B> {}
+
+// SAFETY: see the `Send` impl.
+unsafe impl<'a, A: Sync + 'a, B: Sync + 'a> Sync for PairRef<'a, A, B> {}
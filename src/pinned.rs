@@ -0,0 +1,52 @@
+use core::pin::Pin;
+
+use super::{FatRef, FatRefExt, Metadata};
+
+/// An extension trait for methods on pinned FatRefs.
+///
+/// Tagging a reference never moves or copies the pointee: the data half of a FatRef is
+/// the address of the original value, and the tag only lives in the length half.
+/// That is why a pinned value stays pinned when it is tagged and untagged again.
+///
+/// ```
+/// use std::marker::PhantomPinned;
+/// use std::pin::{pin, Pin};
+/// use fat_pointer_hack::{FatRef, FatRefExt, PinnedFatRefExt};
+///
+/// struct Unmovable {
+///     value: u32,
+///     _pin: PhantomPinned,
+/// }
+///
+/// let pinned = pin!(Unmovable { value: 5, _pin: PhantomPinned });
+/// let addr = &*pinned as *const Unmovable;
+///
+/// let fat: Pin<FatRef<_, _>> = PinnedFatRefExt::from_pinned(pinned.as_ref(), 'p');
+/// assert_eq!(fat.get_tag(), 'p');
+///
+/// let plain = fat.to_plain_pinned();
+/// assert_eq!(plain.value, 5);
+/// assert_eq!(&*plain as *const Unmovable, addr);
+/// ```
+pub trait PinnedFatRefExt<'a> {
+    type Target;
+    type Meta: Metadata;
+    fn from_pinned(pinned: Pin<&'a Self::Target>, tag: Self::Meta) -> Self;
+    fn to_plain_pinned(self) -> Pin<&'a Self::Target>;
+}
+
+impl<'a, P, M: 'a + Metadata> PinnedFatRefExt<'a> for Pin<FatRef<'a, P, M>> {
+    type Target = P;
+    type Meta = M;
+    /// Makes a pinned FatRef from a pinned reference and a tag.
+    fn from_pinned(pinned: Pin<&'a P>, tag: M) -> Self {
+        // The FatRef points at the very same (pinned) value.
+        unsafe { Pin::new_unchecked(FatRef::from_ref(Pin::get_ref(pinned), tag)) }
+    }
+
+    /// Turns this pinned FatRef back into a regular pinned reference.
+    fn to_plain_pinned(self) -> Pin<&'a P> {
+        // The pointee is structurally pinned, FatPointee never moves it.
+        unsafe { self.map_unchecked(|fat| fat.to_plain()) }
+    }
+}
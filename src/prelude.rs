@@ -0,0 +1,13 @@
+//! Everything needed to work with fat references, for glob importing.
+//!
+//! ```
+//! use fat_pointer_hack::prelude::*;
+//!
+//! let x = 5;
+//! let fat: FatRef<i32, char> = (&x).tag('a');
+//! assert_eq!(fat.get_tag(), 'a');
+//! ```
+
+pub use super::{tagged, tagged_mut};
+pub use super::{FatRef, FatRefExt, FatRefMut, FatRefMutExt, Metadata, RefExt};
+pub use super::{ArrayRefExt, AsFatRef, AsFatRefMut, HashTagExt, PinnedFatRefExt, SliceTagExt, TagIterExt};
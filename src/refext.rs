@@ -1,23 +1,202 @@
-use super::{FatRef, FatRefMut, FatRefExt, FatRefMutExt, Metadata};
+use core::ops::Deref;
+
+use super::{fat_ptr, fat_ptr_mut, try_pack_raw, FatRef, FatRefMut, FatRefExt, FatRefMutExt, Metadata, TagOverflowError};
 
 /// An extension trait that is implemented for all references to Sized types automatically.
 /// 
 /// This trait allows you to write (&foo).tag(1234).
 pub trait RefExt<M : Metadata>{
     type Output;
+    type Target;
     fn tag(self, metadata: M) -> Self::Output;
+    fn try_tag(self, metadata: M) -> Result<Self::Output, TagOverflowError>;
+    fn tag_by<F: FnOnce(&Self::Target) -> M>(self, f: F) -> Self::Output;
+
+    /// Same as `tag`, for when the pointee has a `tag` method of its own.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// struct Node;
+    /// impl Node {
+    ///     fn tag(&self, _: usize) {}
+    /// }
+    ///
+    /// let node = Node;
+    /// assert_eq!((&node).with_meta(3).get_tag(), 3);
+    /// ```
+    fn with_meta(self, metadata: M) -> Self::Output
+    where
+        Self: Sized,
+    {
+        self.tag(metadata)
+    }
+
+    /// Tags this reference with the default value of `M`.
+    ///
+    /// `M` is a parameter of the trait, not of the method,
+    /// so name it on the trait when it can't be inferred:
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRef, FatRefExt};
+    /// use std::num::NonZeroUsize;
+    ///
+    /// #[derive(Default, Debug, PartialEq)]
+    /// struct Flags(usize);
+    /// # impl fat_pointer_hack::Metadata for Flags {
+    /// #     fn pack(self) -> fat_pointer_hack::Tag { self.0.pack() }
+    /// #     fn unpack(tag: fat_pointer_hack::Tag) -> Self { Flags(usize::unpack(tag)) }
+    /// # }
+    ///
+    /// let x = 5;
+    /// assert_eq!(RefExt::<Flags>::tag_default(&x).get_tag(), Flags(0));
+    /// assert_eq!(RefExt::<Option<NonZeroUsize>>::tag_default(&x).get_tag(), None);
+    ///
+    /// let inferred: FatRef<i32, usize> = (&x).tag_default();
+    /// assert_eq!(inferred.get_tag(), 0);
+    /// ```
+    fn tag_default(self) -> Self::Output
+    where
+        Self: Sized,
+        M: Default,
+    {
+        self.tag(M::default())
+    }
+
+    /// Tags the target of a smart pointer, rather than the smart pointer itself.
+    ///
+    /// For a `&Box<T>`, the FatRef points into the heap, not at the box on the stack.
+    /// This is only available for shared references.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRef, FatRefExt};
+    /// use std::ops::Deref;
+    /// use std::rc::Rc;
+    ///
+    /// let boxed = Box::new(5);
+    /// let fat: FatRef<i32, char> = (&boxed).tag_deref('b');
+    /// assert_eq!(fat.as_ptr(), &*boxed as *const i32);
+    /// assert_ne!(fat.addr(), &boxed as *const Box<i32> as usize);
+    ///
+    /// let shared = Rc::new(String::from("rc"));
+    /// assert_eq!((&shared).tag_deref(1usize).as_ptr(), Rc::as_ptr(&shared));
+    ///
+    /// struct Wrapper(u8);
+    /// impl Deref for Wrapper {
+    ///     type Target = u8;
+    ///     fn deref(&self) -> &u8 {
+    ///         &self.0
+    ///     }
+    /// }
+    /// let wrapper = Wrapper(7);
+    /// let fat = (&wrapper).tag_deref('w');
+    /// assert_eq!((*fat.to_plain(), fat.get_tag()), (7, 'w'));
+    /// assert_eq!(fat.as_ptr(), &wrapper.0 as *const u8);
+    /// ```
+    ///
+    /// The target has to be Sized, like any pointee:
+    /// ```compile_fail
+    /// # use fat_pointer_hack::RefExt;
+    /// let v = vec![1, 2, 3];
+    /// let fat = (&v).tag_deref(0usize);
+    /// ```
+    fn tag_deref<'b>(self, metadata: M) -> FatRef<'b, <Self::Target as Deref>::Target, M>
+    where
+        Self: Sized + Into<&'b Self::Target>,
+        Self::Target: 'b + Deref,
+        <Self::Target as Deref>::Target: Sized,
+        M: 'b,
+    {
+        FatRef::from_ref(&**self.into(), metadata)
+    }
 }
 
 impl<'a, T: Sized, M : 'a + Metadata> RefExt<M> for &'a T {
     type Output = FatRef<'a, T, M>;
+    type Target = T;
     fn tag(self, metadata: M) -> FatRef<'a, T, M> {
         FatRef::from_ref(self, metadata)
     }
+
+    /// Tags this reference with a value computed from the pointee.
+    ///
+    /// To tag every reference an iterator yields, see `TagIterExt::tag_with`.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt};
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::{Hash, Hasher};
+    ///
+    /// let s = "hello";
+    /// assert_eq!((&s).tag_by(|s| s.len()).get_tag(), 5);
+    ///
+    /// let hash = |s: &&str| {
+    ///     let mut hasher = DefaultHasher::new();
+    ///     s.hash(&mut hasher);
+    ///     hasher.finish() as usize
+    /// };
+    /// let hashed = (&s).tag_by(hash);
+    /// assert_eq!(hashed.get_tag(), hash(&"hello"));
+    ///
+    /// enum Shape {
+    ///     Circle(f64),
+    ///     Square(f64),
+    /// }
+    /// let shape = Shape::Square(2.0);
+    /// let kind = (&shape).tag_by(|shape| match shape {
+    ///     Shape::Circle(_) => 'c',
+    ///     Shape::Square(_) => 's',
+    /// });
+    /// assert_eq!(kind.get_tag(), 's');
+    /// ```
+    fn tag_by<F: FnOnce(&T) -> M>(self, f: F) -> FatRef<'a, T, M> {
+        let metadata = f(self);
+        self.tag(metadata)
+    }
+
+    /// Tags this reference, or returns an error if the tag doesn't fit.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefExt, FatRefMutExt};
+    /// use std::time::Duration;
+    /// let mut x = 5;
+    /// let secs = Duration::from_secs(7);
+    /// assert_eq!((&x).try_tag(secs).unwrap().get_tag(), secs);
+    /// let err = (&x).try_tag(Duration::MAX).unwrap_err();
+    /// assert_eq!((err.type_width(), err.value_width()), (Some(128), None));
+    /// assert_eq!(err.to_string(), "a value of a 128-bit type does not fit into the tag");
+    /// assert_eq!((&mut x).try_tag(secs).unwrap().get_tag(), secs);
+    /// assert!((&mut x).try_tag(Duration::MAX).is_err());
+    /// ```
+    fn try_tag(self, metadata: M) -> Result<FatRef<'a, T, M>, TagOverflowError> {
+        let raw = try_pack_raw(metadata)?;
+        Ok(unsafe { &*fat_ptr(self, raw) })
+    }
 }
 
 impl<'a, T: Sized, M : 'a + Metadata> RefExt<M> for &'a mut T {
     type Output = FatRefMut<'a, T, M>;
+    type Target = T;
     fn tag(self, metadata: M) -> FatRefMut<'a, T, M> {
         FatRefMut::from_ref_mut(self, metadata)
     }
+
+    /// Tags this mutable reference with a value computed from the pointee.
+    ///
+    /// The closure only gets a shared reference, and is done with it
+    /// before the fat pointer is made.
+    /// ```
+    /// # use fat_pointer_hack::{RefExt, FatRefMutExt};
+    /// let mut v = vec![1, 2, 3];
+    /// let tagged = (&mut v).tag_by(|v| v.len());
+    /// assert_eq!(tagged.get_tag(), 3);
+    /// tagged.to_plain_mut().push(4);
+    /// assert_eq!(v, [1, 2, 3, 4]);
+    /// ```
+    fn tag_by<F: FnOnce(&T) -> M>(self, f: F) -> FatRefMut<'a, T, M> {
+        let metadata = f(self);
+        self.tag(metadata)
+    }
+
+    /// Tags this mutable reference, or returns an error if the tag doesn't fit.
+    fn try_tag(self, metadata: M) -> Result<FatRefMut<'a, T, M>, TagOverflowError> {
+        let raw = try_pack_raw(metadata)?;
+        Ok(unsafe { &mut *fat_ptr_mut(self, raw) })
+    }
 }
\ No newline at end of file
@@ -0,0 +1,136 @@
+use core::slice;
+
+use super::{FatRef, FatRefExt, Metadata, TagEnumerate, TagIterExt};
+
+/// An extension trait for slices.
+pub trait SliceTagExt<T> {
+    fn tagged_iter(&self) -> TagEnumerate<slice::Iter<'_, T>>;
+    fn tagged_iter_mut(&mut self) -> TagEnumerate<slice::IterMut<'_, T>>;
+}
+
+impl<T> SliceTagExt<T> for [T] {
+    /// Iterates over the slice, tagging every element with its index.
+    ///
+    /// ```
+    /// # use fat_pointer_hack::{FatRefExt, SliceTagExt};
+    /// let xs = ['a', 'b', 'c'];
+    /// let pairs: Vec<_> = xs.tagged_iter().map(|fat| (*fat.to_plain(), fat.get_tag())).collect();
+    /// assert_eq!(pairs, [('a', 0), ('b', 1), ('c', 2)]);
+    ///
+    /// let backwards: Vec<_> = xs.tagged_iter().rev().map(|fat| fat.get_tag()).collect();
+    /// assert_eq!(backwards, [2, 1, 0]);
+    ///
+    /// let one = [()];
+    /// let mut iter = one.tagged_iter();
+    /// assert_eq!(iter.len(), 1);
+    /// assert_eq!(iter.next().map(|fat| fat.get_tag()), Some(0));
+    /// assert!(iter.next().is_none());
+    ///
+    /// let empty: [u8; 0] = [];
+    /// assert_eq!(empty.tagged_iter().len(), 0);
+    /// assert!(empty.tagged_iter().next_back().is_none());
+    /// ```
+    fn tagged_iter(&self) -> TagEnumerate<slice::Iter<'_, T>> {
+        self.iter().tag_enumerate()
+    }
+
+    /// Iterates mutably over the slice, tagging every element with its index.
+    ///
+    /// Just like with `iter_mut`, the yielded references are disjoint and outlive the iteration:
+    /// ```
+    /// # use fat_pointer_hack::{FatRefMutExt, SliceTagExt};
+    /// let mut xs = [10, 20, 30];
+    /// let all: Vec<_> = xs.tagged_iter_mut().collect();
+    /// for fat in all {
+    ///     *fat.as_mut() += fat.get_tag();
+    /// }
+    /// assert_eq!(xs, [10, 21, 32]);
+    /// ```
+    fn tagged_iter_mut(&mut self) -> TagEnumerate<slice::IterMut<'_, T>> {
+        self.iter_mut().tag_enumerate()
+    }
+}
+
+/// Sorts a slice of FatRefs by their tags, keeping the order of equal tags.
+///
+/// ```
+/// # use fat_pointer_hack::{sort_by_tag, FatRef, FatRefExt, RefExt};
+/// let names = ["low", "high", "mid", "also low"];
+/// let priorities = [1, 3, 2, 1];
+/// let mut refs: Vec<FatRef<&str, usize>> = names.iter().zip(priorities).map(|(n, p)| n.tag(p)).collect();
+///
+/// sort_by_tag(&mut refs);
+/// let sorted: Vec<_> = refs.iter().map(|r| *r.to_plain()).collect();
+/// assert_eq!(sorted, ["low", "also low", "mid", "high"]);
+///
+/// let mut empty: [FatRef<u8, usize>; 0] = [];
+/// sort_by_tag(&mut empty);
+/// let mut one = [(&5).tag(1)];
+/// sort_by_tag(&mut one);
+/// assert_eq!(one[0].get_tag(), 1);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn sort_by_tag<P, M: Metadata + Ord>(refs: &mut [FatRef<'_, P, M>]) {
+    refs.sort_by_key(|r| r.get_tag());
+}
+
+/// Sorts a slice of FatRefs by their tags, without keeping the order of equal tags.
+///
+/// ```
+/// # use fat_pointer_hack::{sort_unstable_by_tag, FatRefExt, RefExt};
+/// let xs = [1, 2, 3];
+/// let mut refs = [(&xs[0]).tag('c'), (&xs[1]).tag('a'), (&xs[2]).tag('b')];
+/// sort_unstable_by_tag(&mut refs);
+/// assert_eq!(refs.map(|r| *r.to_plain()), [2, 3, 1]);
+/// ```
+pub fn sort_unstable_by_tag<P, M: Metadata + Ord>(refs: &mut [FatRef<'_, P, M>]) {
+    refs.sort_unstable_by_key(|r| r.get_tag());
+}
+
+/// Iterates over the runs of FatRefs with equal tags in a slice, together with that tag.
+///
+/// Only neighbours are grouped, so the slice should usually be sorted by tag first.
+/// ```
+/// # use fat_pointer_hack::{group_by_tag, FatRefExt, RefExt};
+/// let xs = [10, 20, 30];
+/// let refs = [(&xs[0]).tag('a'), (&xs[1]).tag('a'), (&xs[2]).tag('b')];
+/// let groups: Vec<_> = group_by_tag(&refs).map(|(tag, group)| (tag, group.len())).collect();
+/// assert_eq!(groups, [('a', 2), ('b', 1)]);
+///
+/// assert_eq!(group_by_tag::<u8, char>(&[]).count(), 0);
+/// ```
+pub fn group_by_tag<'r, 'a, P, M: 'a + Metadata + PartialEq>(
+    refs: &'r [FatRef<'a, P, M>],
+) -> impl Iterator<Item = (M, &'r [FatRef<'a, P, M>])> {
+    refs.chunk_by(|a, b| a.get_tag() == b.get_tag())
+        .map(|group| (group[0].get_tag(), group))
+}
+
+/// Returns the FatRef with the largest tag in a slice, or `None` if the slice is empty.
+///
+/// If several FatRefs have the largest tag, the last one is returned.
+/// ```
+/// # use fat_pointer_hack::{max_by_tag, min_by_tag, FatRefExt, RefExt};
+/// let jobs = ["build", "test", "deploy", "lint"];
+/// let refs = [(&jobs[0]).tag(2), (&jobs[1]).tag(5), (&jobs[2]).tag(9), (&jobs[3]).tag(1)];
+/// assert_eq!(*max_by_tag(&refs).unwrap().to_plain(), "deploy");
+/// assert_eq!(*min_by_tag(&refs).unwrap().to_plain(), "lint");
+///
+/// let (a, b) = ("first", "second");
+/// let tied = [(&a).tag(3), (&b).tag(3)];
+/// assert_eq!(*max_by_tag(&tied).unwrap().to_plain(), "second");
+/// assert_eq!(*min_by_tag(&tied).unwrap().to_plain(), "first");
+///
+/// assert!(max_by_tag::<u8, usize>(&[]).is_none());
+/// assert!(min_by_tag::<u8, usize>(&[]).is_none());
+/// ```
+pub fn max_by_tag<'a, P, M: 'a + Metadata + Ord>(refs: &[FatRef<'a, P, M>]) -> Option<FatRef<'a, P, M>> {
+    refs.iter().copied().max_by_key(|r| r.get_tag())
+}
+
+/// Returns the FatRef with the smallest tag in a slice, or `None` if the slice is empty.
+///
+/// If several FatRefs have the smallest tag, the first one is returned.
+pub fn min_by_tag<'a, P, M: 'a + Metadata + Ord>(refs: &[FatRef<'a, P, M>]) -> Option<FatRef<'a, P, M>> {
+    refs.iter().copied().min_by_key(|r| r.get_tag())
+}
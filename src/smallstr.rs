@@ -0,0 +1,72 @@
+use core::fmt;
+use core::mem::size_of;
+use core::str;
+
+use super::{Metadata, Tag};
+
+const CAPACITY: usize = size_of::<usize>();
+
+/// A short string that is stored inline in the tag, e.g. a label like `"ok"` or `"err"`.
+///
+/// It holds up to `size_of::<usize>()` bytes of UTF-8. Unused bytes are zero,
+/// which is also how the length is recovered, so the string can't end in a `'\0'`.
+///
+/// ```
+/// # use fat_pointer_hack::{RefExt, FatRefExt, SmallStr};
+/// let x = 5;
+/// let ok = (&x).tag(SmallStr::new("ok").unwrap());
+/// assert_eq!(ok.get_tag().as_str(), "ok");
+///
+/// let empty = (&x).tag(SmallStr::new("").unwrap());
+/// assert_eq!(empty.get_tag().as_str(), "");
+///
+/// # #[cfg(target_pointer_width = "64")] {
+/// let full = (&x).tag(SmallStr::new("12345678").unwrap());
+/// assert_eq!(full.get_tag().as_str(), "12345678");
+/// assert!(SmallStr::new("123456789").is_none());
+/// # }
+/// assert!(SmallStr::new("a\0").is_none());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SmallStr([u8; CAPACITY]);
+
+impl SmallStr {
+    /// Makes a SmallStr, or returns `None` if `s` is too long or ends in a `'\0'`.
+    pub fn new(s: &str) -> Option<Self> {
+        if s.len() > CAPACITY || s.ends_with('\0') {
+            return None;
+        }
+        let mut bytes = [0; CAPACITY];
+        bytes[..s.len()].copy_from_slice(s.as_bytes());
+        Some(SmallStr(bytes))
+    }
+
+    /// Returns the string.
+    pub fn as_str(&self) -> &str {
+        let len = self.0.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+        // Only valid UTF-8 is ever stored, see `new` and `unpack`.
+        unsafe { str::from_utf8_unchecked(&self.0[..len]) }
+    }
+}
+
+/// Tags that aren't valid UTF-8 unpack to the empty string.
+#[cfg_attr(trybuild, diagnostic::do_not_recommend)]
+impl Metadata for SmallStr {
+    fn pack(self) -> Tag {
+        Tag(usize::from_ne_bytes(self.0))
+    }
+    fn unpack(val: Tag) -> Self {
+        let bytes = val.0.to_ne_bytes();
+        let len = bytes.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+        match str::from_utf8(&bytes[..len]) {
+            Ok(_) => SmallStr(bytes),
+            Err(_) => SmallStr([0; CAPACITY]),
+        }
+    }
+}
+
+impl fmt::Debug for SmallStr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
@@ -0,0 +1,85 @@
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+
+use super::{FatRef, FatRefExt, Metadata};
+
+/// Restores the previous tag of a FatRef binding when dropped,
+/// created by `FatRefExt::scoped_tag`.
+///
+/// The tag is restored during unwinding as well. The guard derefs to the binding,
+/// so the FatRef can be used (and scoped again) while the guard is alive.
+/// ```
+/// # use fat_pointer_hack::{RefExt, FatRefExt, TagGuard};
+/// let job = "job";
+/// let mut fat = (&job).tag('i');
+/// {
+///     let mut busy = fat.scoped_tag('b');
+///     assert_eq!(busy.get_tag(), 'b');
+///     {
+///         let waiting = busy.scoped_tag('w');
+///         assert_eq!(waiting.get_tag(), 'w');
+///     }
+///     assert_eq!(busy.get_tag(), 'b');
+/// }
+/// assert_eq!(fat.get_tag(), 'i');
+///
+/// fat.scoped_tag('d').keep();
+/// assert_eq!(fat.get_tag(), 'd');
+/// ```
+///
+/// ```
+/// # use fat_pointer_hack::{RefExt, FatRefExt};
+/// use std::panic::{self, AssertUnwindSafe};
+///
+/// let job = "job";
+/// let mut fat = (&job).tag('i');
+/// let result = panic::catch_unwind(AssertUnwindSafe(|| {
+///     let _busy = fat.scoped_tag('b');
+///     panic!("job failed");
+/// }));
+/// assert!(result.is_err());
+/// assert_eq!(fat.get_tag(), 'i');
+/// ```
+pub struct TagGuard<'g, 'a: 'g, P: 'a, M: 'a + Metadata> {
+    binding: &'g mut FatRef<'a, P, M>,
+    saved: Option<usize>,
+    phantom: PhantomData<M>,
+}
+
+impl<'g, 'a: 'g, P: 'a, M: 'a + Metadata> TagGuard<'g, 'a, P, M> {
+    pub(crate) fn new(binding: &'g mut FatRef<'a, P, M>, tag: M) -> Self {
+        let saved = Some(binding.raw_tag());
+        binding.set_tag(tag);
+        TagGuard {
+            binding,
+            saved,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Keeps the temporary tag instead of restoring the previous one.
+    pub fn keep(mut self) {
+        self.saved = None;
+    }
+}
+
+impl<'g, 'a: 'g, P: 'a, M: 'a + Metadata> Deref for TagGuard<'g, 'a, P, M> {
+    type Target = FatRef<'a, P, M>;
+    fn deref(&self) -> &FatRef<'a, P, M> {
+        self.binding
+    }
+}
+
+impl<'g, 'a: 'g, P: 'a, M: 'a + Metadata> DerefMut for TagGuard<'g, 'a, P, M> {
+    fn deref_mut(&mut self) -> &mut FatRef<'a, P, M> {
+        self.binding
+    }
+}
+
+impl<'g, 'a: 'g, P: 'a, M: 'a + Metadata> Drop for TagGuard<'g, 'a, P, M> {
+    fn drop(&mut self) {
+        if let Some(raw) = self.saved {
+            self.binding.set_raw_tag(raw);
+        }
+    }
+}
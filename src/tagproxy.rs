@@ -0,0 +1,35 @@
+use core::marker::PhantomData;
+
+use super::{fat_ptr_mut, FatPointee, Metadata, Tag};
+
+/// A handle to the tag of a FatRefMut binding whose pointee is borrowed elsewhere,
+/// created by `FatRefMutExt::split_tag_mut`.
+///
+/// The proxy only touches the binding itself (the fat pointer), never the pointee.
+pub struct TagProxy<'r, P: 'r, M: 'r> {
+    binding: *mut *mut FatPointee<P, M>,
+    phantom: PhantomData<&'r mut &'r mut FatPointee<P, M>>,
+}
+
+impl<'r, P: 'r, M: 'r + Metadata> TagProxy<'r, P, M> {
+    pub(crate) fn new(binding: *mut *mut FatPointee<P, M>) -> Self {
+        TagProxy {
+            binding,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the current tag of the binding.
+    pub fn get(&self) -> M {
+        let fat = unsafe { *self.binding };
+        M::unpack(Tag((fat as *mut [()]).len()))
+    }
+
+    /// Sets the tag of the binding.
+    pub fn set(&mut self, tag: M) {
+        unsafe {
+            let thin = *self.binding as *mut P;
+            *self.binding = fat_ptr_mut(thin, tag.pack().0);
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use super::{Metadata, Tag};
+
+/// A compact, user-assigned type code that can be used as a tag.
+///
+/// `TypeId` doesn't fit into a tag, so instead every type gets a `u32` code assigned with
+/// the `type_tags!` macro. The code can be matched on later to recover what was tagged.
+///
+/// ```
+/// #[macro_use] extern crate fat_pointer_hack;
+/// use std::fmt::Debug;
+/// use fat_pointer_hack::{DynFatRef, TypeTag};
+///
+/// #[derive(Debug)]
+/// struct Celsius(f32);
+/// #[derive(Debug)]
+/// struct Name(&'static str);
+///
+/// type_tags! {
+///     Celsius = 1,
+///     Name = 2,
+/// }
+///
+/// fn describe(value: DynFatRef<dyn Debug, TypeTag>) -> String {
+///     match value.get_tag() {
+///         tag if tag.is::<Celsius>() => format!("temperature {:?}", &*value),
+///         tag if tag.is::<Name>() => format!("name {:?}", &*value),
+///         _ => String::from("unknown"),
+///     }
+/// }
+///
+/// # fn main() {
+/// let (temperature, name) = (Celsius(21.5), Name("Ferris"));
+/// let values: [DynFatRef<dyn Debug, TypeTag>; 3] = [
+///     DynFatRef::new(&temperature, TypeTag::of::<Celsius>()),
+///     DynFatRef::new(&name, TypeTag::of::<Name>()),
+///     DynFatRef::new(&(), TypeTag(99)),
+/// ];
+/// let described: Vec<_> = values.iter().map(|v| describe(*v)).collect();
+/// assert_eq!(described, ["temperature Celsius(21.5)", "name Name(\"Ferris\")", "unknown"]);
+/// assert_eq!(TypeTag::of::<Name>(), TypeTag(2));
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TypeTag(pub u32);
+
+impl TypeTag {
+    /// Returns the code that was assigned to `T` with `type_tags!`.
+    pub fn of<T: TypeTagged + ?Sized>() -> TypeTag {
+        T::TYPE_TAG
+    }
+
+    /// Checks whether this is the code that was assigned to `T`.
+    pub fn is<T: TypeTagged + ?Sized>(self) -> bool {
+        self == T::TYPE_TAG
+    }
+}
+
+#[cfg_attr(trybuild, diagnostic::do_not_recommend)]
+impl Metadata for TypeTag {
+    fn pack(self) -> Tag {
+        Tag(self.0 as usize)
+    }
+    fn unpack(tag: Tag) -> Self {
+        TypeTag(tag.0 as u32)
+    }
+}
+
+/// Types that have a `TypeTag` assigned, usually implemented with `type_tags!`.
+pub trait TypeTagged {
+    const TYPE_TAG: TypeTag;
+}
@@ -0,0 +1,139 @@
+//! Round-trip tests for every built-in Metadata impl.
+
+extern crate proptest;
+extern crate fat_pointer_hack;
+#[cfg(feature = "half")]
+extern crate half;
+
+use std::mem::size_of;
+use std::net::Ipv4Addr;
+use std::num::NonZeroUsize;
+use std::time::Duration;
+
+use fat_pointer_hack::{BitEqFloat, FatRefExt, Metadata, RefExt, SmallStr, TypeTag};
+#[cfg(target_pointer_width = "64")]
+use fat_pointer_hack::{CharPair, Generation, Pair, Quad};
+use proptest::prelude::*;
+
+/// Tags a reference with `value` and reads the tag back.
+fn round_trip<M: Metadata>(value: M) -> M {
+    let x = 0u8;
+    (&x).tag(value).get_tag()
+}
+
+proptest! {
+    #[test]
+    fn usize_round_trips(x: usize) {
+        prop_assert_eq!(round_trip(x), x);
+    }
+
+    #[test]
+    fn byte_array_round_trips(x: [u8; size_of::<usize>()]) {
+        prop_assert_eq!(round_trip(x), x);
+    }
+
+    #[test]
+    fn option_non_zero_usize_round_trips(x: Option<NonZeroUsize>) {
+        prop_assert_eq!(round_trip(x), x);
+    }
+
+    #[test]
+    fn char_round_trips(x: char) {
+        prop_assert_eq!(round_trip(x), x);
+    }
+
+    #[test]
+    fn f32_round_trips_bitwise(bits: u32) {
+        prop_assert_eq!(round_trip(f32::from_bits(bits)).to_bits(), bits);
+    }
+
+    #[test]
+    fn duration_round_trips(nanos in 0..=usize::MAX as u64) {
+        let d = Duration::from_nanos(nanos);
+        prop_assert_eq!(round_trip(d), d);
+    }
+
+    #[test]
+    fn ipv4_addr_round_trips(bits: u32) {
+        let addr = Ipv4Addr::from(bits);
+        prop_assert_eq!(round_trip(addr), addr);
+    }
+
+    #[test]
+    fn const_ptr_round_trips(addr: usize) {
+        let ptr = std::ptr::without_provenance::<u64>(addr);
+        prop_assert_eq!(round_trip(ptr), ptr);
+    }
+
+    #[test]
+    fn mut_ptr_round_trips(addr: usize) {
+        let ptr = std::ptr::without_provenance_mut::<u64>(addr);
+        prop_assert_eq!(round_trip(ptr), ptr);
+    }
+
+    #[test]
+    fn type_tag_round_trips(x: u32) {
+        prop_assert_eq!(round_trip(TypeTag(x)), TypeTag(x));
+    }
+
+    #[test]
+    fn small_str_round_trips(s in "[^\0]{0,8}") {
+        if let Some(small) = SmallStr::new(&s) {
+            prop_assert_eq!(round_trip(small), small);
+        }
+    }
+
+    #[test]
+    fn bit_eq_f32_round_trips(bits: u32) {
+        let x = BitEqFloat(f32::from_bits(bits));
+        prop_assert_eq!(round_trip(x), x);
+    }
+}
+
+#[cfg(target_pointer_width = "64")]
+proptest! {
+    #[test]
+    fn u32_pair_round_trips(x: (u32, u32)) {
+        prop_assert_eq!(round_trip(x), x);
+    }
+
+    #[test]
+    fn f64_round_trips_bitwise(bits: u64) {
+        prop_assert_eq!(round_trip(f64::from_bits(bits)).to_bits(), bits);
+    }
+
+    #[test]
+    fn bit_eq_f64_round_trips(bits: u64) {
+        let x = BitEqFloat(f64::from_bits(bits));
+        prop_assert_eq!(round_trip(x), x);
+    }
+
+    #[test]
+    fn generation_round_trips(slot: u16, generation: u32) {
+        let x = Generation::new(slot, generation);
+        prop_assert_eq!(round_trip(x), x);
+    }
+
+    #[test]
+    fn pair_round_trips(first: u32, second: u32) {
+        prop_assert_eq!(round_trip(Pair(first, second)), Pair(first, second));
+    }
+
+    #[test]
+    fn quad_round_trips(lanes: [u16; 4]) {
+        prop_assert_eq!(round_trip(Quad(lanes)), Quad(lanes));
+    }
+
+    #[test]
+    fn char_pair_round_trips(first: char, second: char) {
+        prop_assert_eq!(round_trip(CharPair(first, second)), CharPair(first, second));
+    }
+}
+
+#[cfg(feature = "half")]
+proptest! {
+    #[test]
+    fn f16_round_trips_bitwise(bits: u16) {
+        prop_assert_eq!(round_trip(half::f16::from_bits(bits)).to_bits(), bits);
+    }
+}
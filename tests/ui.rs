@@ -0,0 +1,20 @@
+//! Compile tests pinning the crate's diagnostics.
+
+extern crate trybuild;
+
+use std::env;
+
+#[test]
+fn ui() {
+    {
+        let t = trybuild::TestCases::new();
+        t.compile_fail("tests/ui/*.rs");
+        t.pass("tests/ui/f32/tag.rs");
+    }
+
+    // The build script leaves out the impls that need wider tags, as on a 16-bit target.
+    // This is the only test in this file, so no other thread sees the variable.
+    env::set_var("FAT_POINTER_HACK_POINTER_WIDTH", "16");
+    let narrow = trybuild::TestCases::new();
+    narrow.compile_fail("tests/ui/f32/tag.rs");
+}
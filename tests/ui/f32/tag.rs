@@ -0,0 +1,8 @@
+extern crate fat_pointer_hack;
+
+use fat_pointer_hack::RefExt;
+
+fn main() {
+    let x = 0u8;
+    let _ = (&x).tag(1.5f32);
+}
@@ -0,0 +1,11 @@
+extern crate fat_pointer_hack;
+
+use fat_pointer_hack::RefExt;
+
+/// A wrapper that doesn't implement Metadata.
+struct Wrapper(usize);
+
+fn main() {
+    let x = 0u8;
+    let _ = (&x).tag(Wrapper(1));
+}